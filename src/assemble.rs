@@ -92,6 +92,9 @@ impl MockAssembler {
             responders: builder.responders,
             ordered_call_index_range,
             call_counter: builder.count_expectation.into_counter(),
+            item_consumptions: builder.item_consumptions,
+            captures: builder.captures,
+            children: builder.children,
         }
     }
 }
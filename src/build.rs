@@ -1,12 +1,16 @@
 use crate::call_pattern::*;
 use crate::clause::{self, ClauseSealed, TerminalClause};
 use crate::fn_mocker::PatternMatchMode;
-use crate::output::{IntoResponseClone, IntoResponseOnce, Respond, StaticRef};
+use crate::output::{
+    BorrowsFromInputs, FromItems, IntoResponseClone, IntoResponseOnce, Items, Respond, StaticRef,
+};
 use crate::property::*;
 use crate::*;
 
 use std::marker::PhantomData;
 use std::panic;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
 pub(crate) struct DynCallPatternBuilder {
     pub pattern_match_mode: PatternMatchMode,
@@ -14,6 +18,9 @@ pub(crate) struct DynCallPatternBuilder {
     pub responders: Vec<DynCallOrderResponder>,
     pub count_expectation: counter::CallCountExpectation,
     pub current_response_index: usize,
+    pub item_consumptions: Vec<counter::ItemConsumption>,
+    pub captures: Vec<AnyBox>,
+    pub children: Vec<Arc<state::SharedState>>,
 }
 
 impl DynCallPatternBuilder {
@@ -24,6 +31,9 @@ impl DynCallPatternBuilder {
             responders: vec![],
             count_expectation: Default::default(),
             current_response_index: 0,
+            item_consumptions: vec![],
+            captures: vec![],
+            children: vec![],
         }
     }
 }
@@ -67,12 +77,36 @@ impl<'p> BuilderWrapper<'p> {
 
     /// Note: must be called after `push_responder`
     fn quantify(&mut self, times: usize, exactness: counter::Exactness) {
-        let mut builder = self.inner_mut();
+        let builder = self.inner_mut();
 
         builder.count_expectation.add_to_minimum(times, exactness);
         builder.current_response_index += times;
     }
 
+    /// Push a responder producing new items for every call, returning the counter of consumed items.
+    fn push_items_responder<F: MockFn, T>(&mut self, items: Vec<T>) -> Arc<AtomicUsize>
+    where
+        <F::Response as Respond>::Type: FromItems<T>,
+        T: Clone + Send + Sync + 'static,
+    {
+        let consumed = Arc::new(AtomicUsize::new(0));
+        let responder_consumed = consumed.clone();
+
+        self.push_responder(
+            FunctionResponder::<F> {
+                func: Box::new(move |_| {
+                    <F::Response as Respond>::Type::from_items(Items::new(
+                        items.clone(),
+                        responder_consumed.clone(),
+                    ))
+                }),
+            }
+            .into_dyn_responder(),
+        );
+
+        consumed
+    }
+
     fn into_owned(self) -> DynCallPatternBuilder {
         match self {
             Self::Owned(owned) => owned,
@@ -96,6 +130,7 @@ where
     /// The new call pattern will be matched after any previously defined call patterns on the same [Each] instance.
     ///
     /// The method returns a [DefineMultipleResponses], which is used to define how unimock responds to the matched call.
+    #[track_caller]
     pub fn call<'e>(
         &'e mut self,
         matching_fn: &dyn Fn(&mut Matching<F>),
@@ -216,6 +251,34 @@ macro_rules! define_response_common_impl {
                 }
             }
 
+            /// Capture the inputs of every call matched by this call pattern into the given [Captor].
+            ///
+            /// This requires all inputs to be owned, see [capture_with](Self::capture_with) for borrowed inputs.
+            pub fn capture<T>(self, captor: &Captor<T>) -> Self
+            where
+                F: for<'i> MockFn<Inputs<'i> = T>,
+                T: Clone + Send + 'static,
+            {
+                self.capture_with(captor, |inputs| inputs.clone())
+            }
+
+            /// Capture the inputs of every call matched by this call pattern into the given [Captor],
+            /// after converting them to an owned value using the given function.
+            pub fn capture_with<T, C>(mut self, captor: &Captor<T>, func: C) -> Self
+            where
+                C: (for<'i> Fn(&F::Inputs<'i>) -> T) + Send + Sync + 'static,
+                T: Send + 'static,
+            {
+                let captor = captor.clone();
+                self.builder
+                    .inner_mut()
+                    .captures
+                    .push(Box::new(CaptureFn::<F>(Box::new(move |inputs| {
+                        captor.push(func(inputs))
+                    }))));
+                self
+            }
+
             /// Specify the response of the call pattern by calling `Default::default()`.
             pub fn returns_default(mut self) -> Quantify<'p, F, O>
             where
@@ -245,6 +308,95 @@ macro_rules! define_response_common_impl {
                 self.quantify()
             }
 
+            /// Specify the response of the call pattern by invoking the given closure with the callback argument of the call.
+            ///
+            /// This is for methods with exactly one `impl Fn*` parameter, e.g. `fn subscribe(&self, on_event: impl Fn(Event) + Send)`.
+            /// Such parameters are passed to unimock as boxed trait objects, so the closure receives something it can call,
+            /// e.g. `Box<dyn Fn(Event) + Send + '_>`. The other inputs are not passed, use [answers](Self::answers) to access all of them.
+            pub fn invokes_arg<C, R>(self, func: C) -> Quantify<'p, F, O>
+            where
+                F: macro_api::CallbackArg,
+                C: (for<'i> Fn(<F as macro_api::CallbackArg>::Arg<'i>) -> R)
+                    + Send
+                    + Sync
+                    + 'static,
+                R: IntoResponseOnce<F::Response>,
+            {
+                self.answers(move |inputs| func(F::callback_arg(inputs)))
+            }
+
+            /// Specify the response of the call pattern to be an iterator over the given items, e.g. for methods returning `Box<dyn Iterator<Item = T>>`.
+            ///
+            /// When the `futures` feature is enabled, this also works for methods returning streams, e.g. `Pin<Box<dyn Stream<Item = T> + Send>>`.
+            /// There is no separate `returns_stream` responder, streams are served by this one.
+            /// The items of such streams are all immediately ready.
+            ///
+            /// Every matching call gets a new iterator or stream over clones of the items.
+            /// The number of items consumed from them can be verified using [consumed](QuantifyItems::consumed),
+            /// which can be combined with the same quantifiers as any other response, e.g. `.consumed(4).n_times(2)`.
+            pub fn returns_items<T>(
+                mut self,
+                items: impl IntoIterator<Item = T>,
+            ) -> QuantifyItems<'p, F, O>
+            where
+                <F::Response as Respond>::Type: FromItems<T>,
+                T: Clone + Send + Sync + 'static,
+            {
+                let consumed = self
+                    .builder
+                    .push_items_responder::<F, T>(items.into_iter().collect());
+                QuantifyItems {
+                    quantify: self.quantify(),
+                    consumed,
+                }
+            }
+
+            /// Specify the response of the call pattern to be a handle to the [Unimock] instance being called.
+            ///
+            /// This is intended for methods returning `Self`, `Box<Self>` or `Arc<Self>`, like builder-style or factory methods.
+            /// The returned instance is a derived clone, sharing all clauses with the called instance.
+            /// Unlike a regular clone, it also takes part in verification, which happens when the last of these instances gets dropped.
+            /// That makes it possible to mock methods that consume `self` and return a new `Self`.
+            pub fn returns_self(mut self) -> Quantify<'p, F, O>
+            where
+                <F::Response as Respond>::Type: From<Unimock>,
+            {
+                self.builder.push_responder(
+                    InstanceResponder::<F> {
+                        func: Box::new(|unimock| unimock.successor().into()),
+                    }
+                    .into_dyn_responder(),
+                );
+                self.quantify()
+            }
+
+            /// Specify the response of the call pattern to be a new mocked object (a child mock), configured by the given [Clause].
+            ///
+            /// This is intended for factory methods that hand out other mocked objects, e.g. `fn open_session(&self) -> Box<dyn Session>`.
+            /// The response type must implement `From<Unimock>`. For trait objects, that can be written as `impl From<Unimock> for Box<dyn Session>`.
+            ///
+            /// Every matching call returns a handle to the same child mock.
+            /// The clauses of the child mock get verified together with those of the parent instance, even if this call pattern never gets matched,
+            ///     and its errors are reported with a path like `Pool::open_session -> Session::query`.
+            #[track_caller]
+            pub fn returns_mock(mut self, setup: impl Clause) -> Quantify<'p, F, O>
+            where
+                <F::Response as Respond>::Type: From<Unimock>,
+            {
+                let child = Unimock::new_child(setup);
+                self.builder
+                    .inner_mut()
+                    .children
+                    .push(child.shared_state.clone());
+                self.builder.push_responder(
+                    InstanceResponder::<F> {
+                        func: Box::new(move |_| child.clone().into()),
+                    }
+                    .into_dyn_responder(),
+                );
+                self.quantify()
+            }
+
             /// Specify the response of the call pattern to be a static reference to leaked memory.
             ///
             /// The value may be based on the value of input parameters.
@@ -274,6 +426,43 @@ macro_rules! define_response_common_impl {
                 self.quantify()
             }
 
+            /// Specify the response of the call pattern by invoking the given closure, which returns a reference borrowed from the inputs.
+            ///
+            /// This is intended for methods whose output borrows from non-self arguments, e.g. `fn first_token<'a>(&self, s: &'a str) -> &'a str`,
+            /// and unlike [answers_leaked_ref](Self::answers_leaked_ref) it does not leak any memory.
+            ///
+            /// The closure receives the inputs wrapped in [BorrowedInputs] and reads them from its `inputs` field.
+            /// The inputs cannot be passed directly, because the lifetime of the returned reference has to appear in the argument type of the closure.
+            ///
+            /// The output of the method must have the same lifetime as all of its non-self inputs, which is checked at compile time:
+            ///
+            /// ```compile_fail
+            /// # use unimock::*;
+            /// #[unimock(api=ParserMock)]
+            /// trait Parser {
+            ///     fn unrelated<'a>(&self, a: &'a str, b: &str) -> &'a str;
+            /// }
+            ///
+            /// // error: `BorrowsFromInputs` is not implemented for `ParserMock::unrelated`
+            /// let _ = ParserMock::unrelated
+            ///     .next_call(matching!(_, _))
+            ///     .answers_borrowing(|borrowed| borrowed.inputs.0);
+            /// ```
+            pub fn answers_borrowing<C, T>(mut self, func: C) -> Quantify<'p, F, O>
+            where
+                F: MockFn<Response = StaticRef<T>> + BorrowsFromInputs,
+                C: (for<'i> Fn(BorrowedInputs<'i, F>) -> &'i T) + Send + Sync + 'static,
+                T: ?Sized + 'static,
+            {
+                self.builder.push_responder(
+                    BorrowingResponder::<F, T> {
+                        func: Box::new(func),
+                    }
+                    .into_dyn_responder(),
+                );
+                self.quantify()
+            }
+
             /// Prevent this call pattern from succeeding by explicitly panicking with a custom message.
             pub fn panics(mut self, message: impl Into<String>) -> Quantify<'p, F, O> {
                 let message = message.into();
@@ -418,6 +607,14 @@ where
     }
 }
 
+/// The inputs of a call, as passed to the answer function of [answers_borrowing](DefineResponse::answers_borrowing).
+///
+/// This wrapper exists to tie the lifetime of the inputs to the lifetime of the answer.
+pub struct BorrowedInputs<'i, F: MockFn> {
+    /// The inputs of the call.
+    pub inputs: F::Inputs<'i>,
+}
+
 /// Builder for defining how a call pattern gets verified with regards to quantification/counting.
 pub struct Quantify<'p, F: MockFn, O> {
     pub(crate) builder: BuilderWrapper<'p>,
@@ -480,6 +677,60 @@ where
     }
 }
 
+/// Builder for defining how a call pattern responding with items gets verified, see [returns_items](DefineResponse::returns_items).
+///
+/// In addition to quantifying the call pattern, the number of items consumed by the caller can be verified.
+pub struct QuantifyItems<'p, F: MockFn, O> {
+    quantify: Quantify<'p, F, O>,
+    consumed: Arc<AtomicUsize>,
+}
+
+impl<'p, F, O> QuantifyItems<'p, F, O>
+where
+    F: MockFn + 'static,
+    O: Ordering,
+{
+    /// Expect exactly `expected` items to be consumed from the returned iterators or streams in total, verified when the mock is verified.
+    ///
+    /// The call pattern can be quantified further afterwards.
+    pub fn consumed(mut self, expected: usize) -> Quantify<'p, F, O> {
+        self.quantify
+            .builder
+            .inner_mut()
+            .item_consumptions
+            .push(counter::ItemConsumption {
+                consumed: self.consumed,
+                expected,
+            });
+        self.quantify
+    }
+
+    /// Expect this call pattern to be matched exactly once.
+    pub fn once(self) -> QuantifiedResponse<'p, F, O, Exact> {
+        self.quantify.once()
+    }
+
+    /// Expect this call pattern to be matched exactly the specified number of times.
+    pub fn n_times(self, times: usize) -> QuantifiedResponse<'p, F, O, Exact> {
+        self.quantify.n_times(times)
+    }
+
+    /// Expect this call pattern to be matched at least the specified number of times.
+    pub fn at_least_times(self, times: usize) -> QuantifiedResponse<'p, F, O, AtLeast> {
+        self.quantify.at_least_times(times)
+    }
+}
+
+impl<'p, F, O> ClauseSealed for QuantifyItems<'p, F, O>
+where
+    F: MockFn + 'static,
+    O: Ordering,
+{
+    fn deconstruct(self, sink: &mut dyn clause::TerminalSink) -> Result<(), String> {
+        self.quantify.deconstruct(sink)
+    }
+}
+
 /// An exactly quantified response, i.e. the number of times it is expected to respond is an exact number.
 pub struct QuantifiedResponse<'p, F: MockFn, O, R> {
     builder: BuilderWrapper<'p>,
@@ -1,3 +1,4 @@
+use crate::build::BorrowedInputs;
 use crate::cell::{Cell, CloneCell, FactoryCell};
 use crate::debug;
 use crate::macro_api::MismatchReporter;
@@ -37,6 +38,9 @@ pub(crate) struct CallPattern {
     pub responders: Vec<DynCallOrderResponder>,
     pub ordered_call_index_range: std::ops::Range<usize>,
     pub call_counter: counter::CallCounter,
+    pub item_consumptions: Vec<counter::ItemConsumption>,
+    pub captures: Vec<AnyBox>,
+    pub children: Vec<std::sync::Arc<crate::state::SharedState>>,
 }
 
 impl CallPattern {
@@ -59,9 +63,17 @@ impl CallPattern {
         }
     }
 
+    /// Store the inputs of a matched call in the captors of this pattern
+    pub fn capture_inputs<F: MockFn>(&self, inputs: &F::Inputs<'_>) -> PatternResult<()> {
+        for capture in &self.captures {
+            (downcast_box::<CaptureFn<F>>(capture)?.0)(inputs);
+        }
+        Ok(())
+    }
+
     pub fn debug_location(&self, pat_index: PatIndex) -> debug::CallPatternLocation {
-        if let Some(debug) = self.input_matcher.matcher_debug {
-            debug::CallPatternLocation::Debug(debug)
+        if let Some(debug) = &self.input_matcher.matcher_debug {
+            debug::CallPatternLocation::Debug(debug.clone())
         } else {
             debug::CallPatternLocation::PatIndex(pat_index)
         }
@@ -78,6 +90,7 @@ pub(crate) struct DynInputMatcher {
 }
 
 impl DynInputMatcher {
+    #[track_caller]
     pub fn from_matching_fn<F: MockFn>(matching_fn: &dyn Fn(&mut Matching<F>)) -> Self {
         let mut builder = Matching::new();
         matching_fn(&mut builder);
@@ -109,6 +122,10 @@ pub(crate) struct MatchingFnDebug<F: MockFn>(
     pub  Box<dyn (for<'i> Fn(&F::Inputs<'i>, &mut MismatchReporter) -> bool) + Send + Sync>,
 );
 
+pub(crate) struct CaptureFn<F: MockFn>(
+    #[allow(clippy::type_complexity)] pub Box<dyn for<'i> Fn(&F::Inputs<'i>) + Send + Sync>,
+);
+
 pub(crate) struct DynCallOrderResponder {
     pub response_index: usize,
     pub responder: DynResponder,
@@ -118,6 +135,8 @@ pub(crate) enum DynResponder {
     Cell(DynCellResponder),
     Borrow(DynBorrowResponder),
     Function(DynFunctionResponder),
+    Instance(DynInstanceResponder),
+    Borrowing(DynBorrowingResponder),
     Panic(String),
     Unmock,
 }
@@ -173,6 +192,8 @@ impl DynResponder {
 pub(crate) struct DynCellResponder(AnyBox);
 pub(crate) struct DynBorrowResponder(AnyBox);
 pub(crate) struct DynFunctionResponder(AnyBox);
+pub(crate) struct DynInstanceResponder(AnyBox);
+pub(crate) struct DynBorrowingResponder(AnyBox);
 
 pub trait DowncastResponder<F: MockFn> {
     type Downcasted;
@@ -204,6 +225,22 @@ impl<F: MockFn> DowncastResponder<F> for DynFunctionResponder {
     }
 }
 
+impl<F: MockFn> DowncastResponder<F> for DynInstanceResponder {
+    type Downcasted = InstanceResponder<F>;
+
+    fn downcast(&self) -> PatternResult<&Self::Downcasted> {
+        downcast_box(&self.0)
+    }
+}
+
+impl DynBorrowingResponder {
+    pub fn downcast<F: MockFn, T: ?Sized + 'static>(
+        &self,
+    ) -> PatternResult<&BorrowingResponder<F, T>> {
+        downcast_box(&self.0)
+    }
+}
+
 pub(crate) struct CellResponder<F: MockFn> {
     pub cell: Box<dyn Cell<<F::Response as Respond>::Type>>,
 }
@@ -217,6 +254,18 @@ pub(crate) struct FunctionResponder<F: MockFn> {
     pub func: Box<dyn (for<'i> Fn(F::Inputs<'i>) -> <F::Response as Respond>::Type) + Send + Sync>,
 }
 
+/// Responder that computes its response from the [Unimock] instance being called.
+pub(crate) struct InstanceResponder<F: MockFn> {
+    #[allow(clippy::type_complexity)]
+    pub func: Box<dyn Fn(&Unimock) -> <F::Response as Respond>::Type + Send + Sync>,
+}
+
+/// Responder that computes an output borrowed from the inputs of the call.
+pub(crate) struct BorrowingResponder<F: MockFn, T: ?Sized + 'static> {
+    #[allow(clippy::type_complexity)]
+    pub func: Box<dyn (for<'i> Fn(BorrowedInputs<'i, F>) -> &'i T) + Send + Sync>,
+}
+
 impl<F: MockFn> CellResponder<F> {
     pub fn into_dyn_responder(self) -> DynResponder {
         DynResponder::Cell(DynCellResponder(Box::new(self)))
@@ -238,6 +287,18 @@ impl<F: MockFn> FunctionResponder<F> {
     }
 }
 
+impl<F: MockFn> InstanceResponder<F> {
+    pub fn into_dyn_responder(self) -> DynResponder {
+        DynResponder::Instance(DynInstanceResponder(Box::new(self)))
+    }
+}
+
+impl<F: MockFn, T: ?Sized + 'static> BorrowingResponder<F, T> {
+    pub fn into_dyn_responder(self) -> DynResponder {
+        DynResponder::Borrowing(DynBorrowingResponder(Box::new(self)))
+    }
+}
+
 fn find_responder_by_call_index(
     responders: &[DynCallOrderResponder],
     call_index: usize,
@@ -0,0 +1,216 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The maximum number of calls recorded in a call tree. Later calls are only counted.
+const MAX_NODES: usize = 1000;
+
+/// A record of the nesting of calls into a [Unimock](crate::Unimock) instance.
+///
+/// Unmocked functions may call back into the instance, so a single top-level call
+/// can fan out into a tree of mocked and unmocked calls.
+///
+/// Every call refers to the call it was made from, if any, which is found in the [CurrentCalls] of the calling thread or task.
+/// That way concurrent calls from several threads or tasks do not get nested into each other.
+#[derive(Default)]
+pub(crate) struct CallTree {
+    nodes: Vec<Node>,
+    // The number of calls not recorded because of the size limit
+    omitted: usize,
+}
+
+struct Node {
+    parent: Option<usize>,
+    call: String,
+    call_site: &'static std::panic::Location<'static>,
+    outcome: Outcome,
+    output: Option<String>,
+}
+
+/// How a call was answered.
+pub(crate) enum Outcome {
+    Pending,
+    Mocked(String),
+    Unmocked,
+    Failed,
+}
+
+impl CallTree {
+    /// Record a call made from the given parent call, returning its node, or [None] if the tree is full.
+    pub fn enter(
+        &mut self,
+        parent: Option<usize>,
+        call: String,
+        call_site: &'static std::panic::Location<'static>,
+    ) -> Option<usize> {
+        if self.nodes.len() == MAX_NODES {
+            self.omitted += 1;
+            return None;
+        }
+        self.nodes.push(Node {
+            parent,
+            call,
+            call_site,
+            outcome: Outcome::Pending,
+            output: None,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    pub fn set_outcome(&mut self, node: usize, outcome: Outcome) {
+        self.nodes[node].outcome = outcome;
+    }
+
+    /// Register that the call returned the given output.
+    pub fn exit(&mut self, node: usize, output: String) {
+        self.nodes[node].output = Some(output);
+    }
+}
+
+impl Display for CallTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut children: Vec<Vec<usize>> = vec![vec![]; self.nodes.len()];
+        let mut roots = vec![];
+        for (index, node) in self.nodes.iter().enumerate() {
+            match node.parent {
+                Some(parent) => children[parent].push(index),
+                None => roots.push(index),
+            }
+        }
+
+        writeln!(f, "Call tree:")?;
+
+        // Depth first, without recursion, as unmocked calls may nest deeply
+        let mut pending: Vec<(usize, usize)> = roots.iter().rev().map(|root| (*root, 0)).collect();
+        while let Some((index, depth)) = pending.pop() {
+            let node = &self.nodes[index];
+            write!(
+                f,
+                "{:indent$}{} at {} ",
+                "",
+                node.call,
+                node.call_site,
+                indent = (depth + 1) * 2
+            )?;
+            match &node.outcome {
+                Outcome::Pending => write!(f, "[pending]")?,
+                Outcome::Mocked(pattern) => write!(f, "[mocked by {pattern}]")?,
+                Outcome::Unmocked => write!(f, "[unmocked]")?,
+                Outcome::Failed => write!(f, "[failed]")?,
+            }
+            if let Some(output) = &node.output {
+                write!(f, " -> {output}")?;
+            }
+            writeln!(f)?;
+
+            pending.extend(
+                children[index]
+                    .iter()
+                    .rev()
+                    .map(|child| (*child, depth + 1)),
+            );
+        }
+
+        if self.omitted > 0 {
+            writeln!(f, "  ({} more calls not recorded)", self.omitted)?;
+        }
+        Ok(())
+    }
+}
+
+/// A call that has not returned yet, recorded in the call tree of the state with the given id.
+#[derive(Clone, Copy)]
+pub(crate) struct CurrentCall {
+    pub state_id: usize,
+    // The node of the call, if it was recorded
+    pub node: Option<usize>,
+}
+
+/// The calls that have not returned yet in the current thread, innermost last.
+///
+/// Futures of async methods carry their own [CurrentCalls], which are installed while they are polled.
+#[derive(Clone, Default)]
+pub(crate) struct CurrentCalls(Vec<CurrentCall>);
+
+thread_local! {
+    static CURRENT_CALLS: RefCell<CurrentCalls> = RefCell::new(CurrentCalls::default());
+}
+
+impl CurrentCalls {
+    pub fn len() -> usize {
+        CURRENT_CALLS.with(|calls| calls.borrow().0.len())
+    }
+
+    /// The innermost call into the state with the given id.
+    pub fn innermost(state_id: usize) -> Option<CurrentCall> {
+        CURRENT_CALLS.with(|calls| {
+            calls
+                .borrow()
+                .0
+                .iter()
+                .rev()
+                .find(|call| call.state_id == state_id)
+                .copied()
+        })
+    }
+
+    pub fn push(call: CurrentCall) {
+        CURRENT_CALLS.with(|calls| calls.borrow_mut().0.push(call));
+    }
+
+    /// The call at the given position, if there is one.
+    pub fn get(position: usize) -> Option<CurrentCall> {
+        CURRENT_CALLS.with(|calls| calls.borrow().0.get(position).copied())
+    }
+
+    /// Forget the calls from the given position onwards, as they have returned or unwound.
+    pub fn truncate(len: usize) {
+        CURRENT_CALLS.with(|calls| calls.borrow_mut().0.truncate(len));
+    }
+
+    fn snapshot() -> Self {
+        CURRENT_CALLS.with(|calls| calls.borrow().clone())
+    }
+
+    fn swap(&mut self) {
+        CURRENT_CALLS.with(|calls| std::mem::swap(self, &mut calls.borrow_mut()));
+    }
+}
+
+/// A future evaluated in the context of the calls that were current when it was created,
+/// no matter which thread polls it or which other futures are polled in between.
+pub struct TraceFuture<Fut> {
+    future: Pin<Box<Fut>>,
+    calls: CurrentCalls,
+}
+
+impl<Fut> TraceFuture<Fut> {
+    pub(crate) fn new(future: Fut) -> Self {
+        Self {
+            future: Box::pin(future),
+            calls: CurrentCalls::snapshot(),
+        }
+    }
+}
+
+impl<Fut: Future> Future for TraceFuture<Fut> {
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Swaps the calls of the future back out of the thread, also when polling panics
+        struct Installed<'a>(&'a mut CurrentCalls);
+
+        impl<'a> Drop for Installed<'a> {
+            fn drop(&mut self) {
+                self.0.swap();
+            }
+        }
+
+        let this = self.get_mut();
+        this.calls.swap();
+        let _installed = Installed(&mut this.calls);
+        this.future.as_mut().poll(cx)
+    }
+}
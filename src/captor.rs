@@ -0,0 +1,97 @@
+use std::sync::{Arc, Mutex};
+
+/// Shared storage for inputs captured from mocked calls.
+///
+/// A captor is attached to a call pattern using [capture](crate::build::DefineMultipleResponses::capture)
+/// or [capture_with](crate::build::DefineMultipleResponses::capture_with).
+/// Every call matched by that call pattern stores its (converted) inputs in the captor,
+/// so they can be asserted after the code under test has returned,
+/// instead of asserting within an `answers` closure.
+///
+/// [Cloning](Clone::clone) a captor produces a handle to the same storage.
+///
+/// # Example
+/// ```rust
+/// use unimock::*;
+///
+/// #[unimock(api=EmailerMock)]
+/// trait Emailer {
+///     fn send(&self, to: String, subject: String);
+/// }
+///
+/// let captor = Captor::new();
+/// let u = Unimock::new(
+///     EmailerMock::send
+///         .each_call(matching!(_, _))
+///         .capture(&captor)
+///         .returns(())
+///         .n_times(2),
+/// );
+///
+/// u.send("alice".to_string(), "hi".to_string());
+/// u.send("bob".to_string(), "hello".to_string());
+///
+/// assert_eq!(
+///     vec![
+///         ("alice".to_string(), "hi".to_string()),
+///         ("bob".to_string(), "hello".to_string()),
+///     ],
+///     captor.values()
+/// );
+/// ```
+pub struct Captor<T> {
+    values: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Captor<T> {
+    /// Create a new, empty captor.
+    pub fn new() -> Self {
+        Self {
+            values: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// The captured values, in call order.
+    pub fn values(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.values.lock().unwrap().clone()
+    }
+
+    /// The most recently captured value.
+    pub fn last(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.values.lock().unwrap().last().cloned()
+    }
+
+    /// The number of captured values.
+    pub fn len(&self) -> usize {
+        self.values.lock().unwrap().len()
+    }
+
+    /// Whether no values have been captured.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn push(&self, value: T) {
+        self.values.lock().unwrap().push(value);
+    }
+}
+
+impl<T> Clone for Captor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T> Default for Captor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
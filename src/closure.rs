@@ -0,0 +1,118 @@
+use crate::output::Output;
+use crate::*;
+
+use std::marker::PhantomData;
+
+/// A mocked closure, i.e. a callable value whose behaviour is controlled by a single [MockFn].
+///
+/// Many APIs accept callbacks in the form of `impl Fn(Request) -> Response`.
+/// A `MockClosure` makes it possible to pass a mocked callback to those APIs,
+/// configured using the same kind of [Clause] that is used to set up a [Unimock].
+///
+/// The [MockFn] used to describe the closure comes from a `#[unimock]` trait with a single method
+/// that has the same signature as the callback.
+///
+/// A `MockClosure` owns its own [Unimock] instance, and its calls are verified when the _original_ instance gets dropped,
+/// using the same interaction checks as for mocked trait methods.
+/// [Cloning](Clone::clone) a `MockClosure` produces a derived instance, with the same semantics as [cloning Unimock](Unimock#clone-semantics).
+///
+/// # Example
+/// ```rust
+/// use unimock::*;
+///
+/// #[unimock(api=HandlerMock)]
+/// trait Handler {
+///     fn handle(&self, request: i32) -> String;
+/// }
+///
+/// fn serve(requests: &[i32], handler: impl Fn(i32) -> String) -> Vec<String> {
+///     requests.iter().map(|request| handler(*request)).collect()
+/// }
+///
+/// let handler = MockClosure::<HandlerMock::handle>::new(
+///     HandlerMock::handle.stub(|each| {
+///         each.call(matching!(1)).returns("one").once();
+///         each.call(matching!(_)).answers(|request| request.to_string());
+///     }),
+/// );
+///
+/// assert_eq!(
+///     vec!["one".to_string(), "2".to_string()],
+///     serve(&[1, 2], handler.into_fn())
+/// );
+/// ```
+pub struct MockClosure<F: MockFn> {
+    unimock: Unimock,
+    mock_fn: PhantomData<fn() -> F>,
+}
+
+impl<F: MockFn> MockClosure<F> {
+    /// Construct a mocked closure for the [MockFn] `F`, which strictly adheres to the description in the passed [Clause].
+    ///
+    /// The function is selected with a turbofish, e.g. `MockClosure::<HandlerMock::handle>::new(setup)`.
+    /// The `setup` works like in [Unimock::new].
+    #[track_caller]
+    pub fn new(setup: impl Clause) -> Self {
+        Self {
+            unimock: Unimock::new(setup),
+            mock_fn: PhantomData,
+        }
+    }
+
+    /// Call the closure, evaluating the [MockFn] with the given inputs.
+    ///
+    /// For a function with more than one parameter, the inputs are passed as a tuple.
+    #[track_caller]
+    pub fn call<'u, 'i>(
+        &'u self,
+        inputs: F::Inputs<'i>,
+    ) -> <F::Output<'u> as Output<'u, F::Response>>::Type {
+        let trace = macro_api::trace_start(&self.unimock);
+        let output = macro_api::eval::<F>(&self.unimock, inputs).unwrap(&self.unimock);
+        macro_api::trace_output(trace, output, |_| None)
+    }
+
+    /// Turn this mocked closure into a Rust closure taking the inputs of the [MockFn] as argument.
+    ///
+    /// This works for functions with owned outputs, since the closure cannot lend out data borrowed from itself.
+    /// For functions with more than one parameter, the closure takes the inputs as a tuple,
+    /// see [into_fn2](Self::into_fn2) and friends for closures taking them as separate arguments.
+    pub fn into_fn<R>(self) -> impl for<'i> Fn(F::Inputs<'i>) -> R + Send + Sync + 'static
+    where
+        for<'u> F::Output<'u>: Output<'u, F::Response, Type = R>,
+    {
+        move |inputs| self.call(inputs)
+    }
+}
+
+macro_rules! into_fn_n {
+    ($fn_ident:ident, $count:literal, $($arg:ident),+) => {
+        impl<F: MockFn> MockClosure<F> {
+            #[doc = concat!("Turn this mocked closure into a Rust closure taking the ", $count, " inputs of the [MockFn] as separate arguments.")]
+            ///
+            /// This works for functions with owned inputs and outputs.
+            /// Callbacks taking references can instead be built from [call](Self::call), e.g. `|a, b| closure.call((a, b))`.
+            pub fn $fn_ident<$($arg,)+ R>(self) -> impl Fn($($arg),+) -> R + Send + Sync + 'static
+            where
+                F: for<'i> MockFn<Inputs<'i> = ($($arg,)+)>,
+                for<'u> F::Output<'u>: Output<'u, F::Response, Type = R>,
+            {
+                #[allow(non_snake_case)]
+                move |$($arg),+| self.call(($($arg,)+))
+            }
+        }
+    };
+}
+
+into_fn_n!(into_fn2, "two", A, B);
+into_fn_n!(into_fn3, "three", A, B, C);
+into_fn_n!(into_fn4, "four", A, B, C, D);
+
+impl<F: MockFn> Clone for MockClosure<F> {
+    fn clone(&self) -> Self {
+        Self {
+            unimock: self.unimock.clone(),
+            mock_fn: PhantomData,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::debug;
 use crate::error::MockError;
 
+use std::sync::Arc;
 use std::{fmt::Display, sync::atomic::AtomicUsize};
 
 pub(crate) struct CallCounter {
@@ -26,14 +27,24 @@ impl CallCounter {
         match self.expectation.exactness {
             Exactness::Exact => {
                 if actual_calls.0 != lower_bound.0 {
-                    let pattern = debug_fn();
-                    errors.push(MockError::FailedVerification(format!("{name}: Expected {pattern} to match exactly {lower_bound}, but it actually matched {actual_calls}.")));
+                    errors.push(MockError::CallCountNotMatched {
+                        name,
+                        pattern: debug_fn(),
+                        exactness: Exactness::Exact,
+                        expected: lower_bound,
+                        actual: actual_calls,
+                    });
                 }
             }
             Exactness::AtLeast | Exactness::AtLeastPlusOne => {
                 if actual_calls.0 < lower_bound.0 {
-                    let pattern = debug_fn();
-                    errors.push(MockError::FailedVerification(format!("{name}: Expected {pattern} to match at least {lower_bound}, but it actually matched {actual_calls}.")));
+                    errors.push(MockError::CallCountNotMatched {
+                        name,
+                        pattern: debug_fn(),
+                        exactness: Exactness::AtLeast,
+                        expected: lower_bound,
+                        actual: actual_calls,
+                    });
                 }
             }
         };
@@ -42,6 +53,31 @@ impl CallCounter {
     }
 }
 
+/// The expected number of items consumed from the iterators or streams returned by a call pattern.
+pub(crate) struct ItemConsumption {
+    pub consumed: Arc<AtomicUsize>,
+    pub expected: usize,
+}
+
+impl ItemConsumption {
+    pub fn verify(
+        &self,
+        name: &'static str,
+        debug_fn: impl Fn() -> debug::CallPatternDebug,
+        errors: &mut Vec<MockError>,
+    ) {
+        let consumed = self.consumed.load(std::sync::atomic::Ordering::SeqCst);
+        if consumed != self.expected {
+            errors.push(MockError::ItemsNotConsumed {
+                name,
+                pattern: debug_fn(),
+                expected: self.expected,
+                consumed,
+            });
+        }
+    }
+}
+
 pub(crate) struct CallCountExpectation {
     minimum: usize,
     exactness: Exactness,
@@ -85,6 +121,7 @@ impl Default for CallCountExpectation {
     }
 }
 
+#[derive(Clone, Copy)]
 pub(crate) enum Exactness {
     Exact,
     AtLeast,
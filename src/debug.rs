@@ -2,16 +2,51 @@ use crate::{call_pattern::PatIndex, DynMockFn};
 
 #[derive(Clone)]
 pub(crate) struct FnActualCall {
-    pub mock_fn: crate::DynMockFn,
-    pub inputs_debug: String,
+    inner: Box<FnActualCallInner>,
+}
+
+impl FnActualCall {
+    pub fn new(
+        mock_fn: DynMockFn,
+        inputs_debug: String,
+        call_site: &'static std::panic::Location<'static>,
+    ) -> Self {
+        Self {
+            inner: Box::new(FnActualCallInner {
+                mock_fn,
+                inputs_debug,
+                call_site,
+            }),
+        }
+    }
+
+    /// Where the code under test invoked the mocked method.
+    pub fn call_site(&self) -> &'static std::panic::Location<'static> {
+        self.inner.call_site
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.inner.mock_fn.name
+    }
+
+    pub fn inputs_debug(&self) -> &str {
+        &self.inner.inputs_debug
+    }
 }
 
 impl std::fmt::Display for FnActualCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.mock_fn.name, self.inputs_debug)
+        write!(f, "{}{}", self.inner.mock_fn.name, self.inner.inputs_debug)
     }
 }
 
+#[derive(Clone)]
+struct FnActualCallInner {
+    mock_fn: DynMockFn,
+    inputs_debug: String,
+    call_site: &'static std::panic::Location<'static>,
+}
+
 #[derive(Clone)]
 pub(crate) struct CallPatternDebug {
     inner: Box<CallPatternDebugInner>,
@@ -25,6 +60,16 @@ impl CallPatternDebug {
     }
 }
 
+impl CallPatternDebug {
+    /// The source location where the call pattern was defined, if known.
+    pub fn location(&self) -> Option<(&'static str, u32)> {
+        match &self.inner.location {
+            CallPatternLocation::Debug(InputMatcherDebug { file, line, .. }) => Some((file, *line)),
+            CallPatternLocation::PatIndex(_) => None,
+        }
+    }
+}
+
 impl std::fmt::Display for CallPatternDebug {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.inner.location {
@@ -46,6 +91,42 @@ impl std::fmt::Display for CallPatternDebug {
     }
 }
 
+/// Summary of the call pattern that came closest to matching a call.
+#[derive(Clone)]
+pub(crate) struct ClosestPatternDebug {
+    pub pat_index: PatIndex,
+    pub pattern: CallPatternDebug,
+    pub differing_inputs: usize,
+    pub guard_failed: bool,
+    /// The number of call patterns whose mismatches were left out of the report.
+    pub omitted_patterns: usize,
+}
+
+impl std::fmt::Display for ClosestPatternDebug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let input_count = self.pattern.inner.mock_fn.input_names.len();
+        write!(f, "Closest pattern: {} {} ", self.pat_index, self.pattern)?;
+        if self.guard_failed && self.differing_inputs == 0 {
+            write!(f, "(all inputs matched, but the guard failed).")?;
+        } else {
+            write!(
+                f,
+                "({} of {input_count} inputs differ).",
+                self.differing_inputs
+            )?;
+        }
+        if self.omitted_patterns > 0 {
+            let plural = if self.omitted_patterns == 1 { "" } else { "s" };
+            write!(
+                f,
+                " Mismatches of {} other call pattern{plural} omitted, use `Verbosity::Verbose` to report all.",
+                self.omitted_patterns
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 struct CallPatternDebugInner {
     mock_fn: crate::DynMockFn,
@@ -58,13 +139,68 @@ pub(crate) enum CallPatternLocation {
     PatIndex(PatIndex),
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub(crate) struct InputMatcherDebug {
-    pub pat_debug: &'static str,
+    pub pat_debug: std::borrow::Cow<'static, str>,
     pub file: &'static str,
     pub line: u32,
 }
 
+/// Truncate a rendered value to at most `max_len` bytes, marking how much was elided.
+pub(crate) fn truncate(value: &str, max_len: usize) -> std::borrow::Cow<'_, str> {
+    if value.len() <= max_len {
+        return std::borrow::Cow::Borrowed(value);
+    }
+
+    let end = floor_char_boundary(value, max_len);
+
+    std::borrow::Cow::Owned(format!(
+        "{}… {} more bytes",
+        &value[..end],
+        value.len() - end
+    ))
+}
+
+/// Truncate a rendered diff to at most `max_len` bytes, preferably at the end of a line.
+///
+/// Diffs may contain ANSI escape codes, so when a line has to be cut, its styling is reset.
+pub(crate) fn truncate_diff(diff: &str, max_len: usize) -> std::borrow::Cow<'_, str> {
+    if diff.len() <= max_len {
+        return std::borrow::Cow::Borrowed(diff);
+    }
+
+    let end = floor_char_boundary(diff, max_len);
+    let (kept, reset) = match diff[..end].rfind('\n') {
+        Some(newline) => (&diff[..newline + 1], ""),
+        None => (without_unterminated_escape(&diff[..end]), ANSI_RESET),
+    };
+
+    std::borrow::Cow::Owned(format!(
+        "{kept}{reset}… {} more bytes",
+        diff.len() - kept.len()
+    ))
+}
+
+#[cfg(feature = "pretty-print")]
+const ANSI_RESET: &str = "\x1b[0m";
+#[cfg(not(feature = "pretty-print"))]
+const ANSI_RESET: &str = "";
+
+// Cut off an ANSI escape sequence which is missing its final `m`
+fn without_unterminated_escape(value: &str) -> &str {
+    match value.rfind('\x1b') {
+        Some(escape) if !value[escape..].contains('m') => &value[..escape],
+        _ => value,
+    }
+}
+
+fn floor_char_boundary(value: &str, mut index: usize) -> usize {
+    while !value.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 pub(crate) fn filter_questionmark(string: String) -> Option<String> {
     if string == "?" {
         None
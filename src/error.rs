@@ -1,3 +1,4 @@
+use crate::counter::{Exactness, NCalls};
 use crate::{debug, mismatch::Mismatches};
 
 pub(crate) type MockResult<T> = Result<T, MockError>;
@@ -17,6 +18,7 @@ pub(crate) enum MockError {
     },
     NoMatchingCallPatterns {
         fn_call: debug::FnActualCall,
+        closest: Option<Box<debug::ClosestPatternDebug>>,
         mismatches: Mismatches,
     },
     NoOutputAvailableForCallPattern {
@@ -41,7 +43,23 @@ pub(crate) enum MockError {
         fn_call: debug::FnActualCall,
         pattern: debug::CallPatternDebug,
     },
-    FailedVerification(String),
+    CannotBorrowFromInputs {
+        fn_call: debug::FnActualCall,
+        pattern: debug::CallPatternDebug,
+    },
+    CallCountNotMatched {
+        name: &'static str,
+        pattern: debug::CallPatternDebug,
+        exactness: Exactness,
+        expected: NCalls,
+        actual: NCalls,
+    },
+    ItemsNotConsumed {
+        name: &'static str,
+        pattern: debug::CallPatternDebug,
+        expected: usize,
+        consumed: usize,
+    },
     CannotUnmock {
         name: &'static str,
     },
@@ -52,8 +70,77 @@ pub(crate) enum MockError {
     },
 }
 
+impl MockError {
+    pub(crate) fn fn_call(&self) -> Option<&debug::FnActualCall> {
+        match self {
+            Self::Downcast { fn_call, .. }
+            | Self::NoMockImplementation { fn_call }
+            | Self::NoMatcherFunction { fn_call, .. }
+            | Self::NoMatchingCallPatterns { fn_call, .. }
+            | Self::NoOutputAvailableForCallPattern { fn_call, .. }
+            | Self::CallOrderNotMatchedForMockFn { fn_call, .. }
+            | Self::InputsNotMatchedInCallOrder { fn_call, .. }
+            | Self::CannotReturnValueMoreThanOnce { fn_call, .. }
+            | Self::CannotBorrowFromInputs { fn_call, .. }
+            | Self::ExplicitPanic { fn_call, .. } => Some(fn_call),
+            Self::MockNeverCalled { .. }
+            | Self::CallCountNotMatched { .. }
+            | Self::ItemsNotConsumed { .. }
+            | Self::CannotUnmock { .. } => None,
+        }
+    }
+
+    /// The call pattern involved in the error, if any.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn pattern(&self) -> Option<&debug::CallPatternDebug> {
+        match self {
+            Self::Downcast { pattern, .. }
+            | Self::NoMatcherFunction { pattern, .. }
+            | Self::NoOutputAvailableForCallPattern { pattern, .. }
+            | Self::InputsNotMatchedInCallOrder { pattern, .. }
+            | Self::CannotReturnValueMoreThanOnce { pattern, .. }
+            | Self::CannotBorrowFromInputs { pattern, .. }
+            | Self::CallCountNotMatched { pattern, .. }
+            | Self::ItemsNotConsumed { pattern, .. }
+            | Self::ExplicitPanic { pattern, .. } => Some(pattern),
+            Self::NoMatchingCallPatterns { closest, .. } => {
+                closest.as_ref().map(|closest| &closest.pattern)
+            }
+            Self::CallOrderNotMatchedForMockFn { expected, .. } => expected.as_ref(),
+            Self::NoMockImplementation { .. }
+            | Self::MockNeverCalled { .. }
+            | Self::CannotUnmock { .. } => None,
+        }
+    }
+}
+
 impl std::fmt::Display for MockError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fn_call() {
+            Some(fn_call) => {
+                let message = MessageDisplay(self).to_string();
+                write!(
+                    f,
+                    "{}\nCalled at {}.",
+                    message.trim_end(),
+                    fn_call.call_site()
+                )
+            }
+            None => MessageDisplay(self).fmt(f),
+        }
+    }
+}
+
+struct MessageDisplay<'e>(&'e MockError);
+
+impl std::fmt::Display for MessageDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_message(f)
+    }
+}
+
+impl MockError {
+    fn fmt_message(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Downcast { fn_call, pattern } => {
                 write!(f, "{fn_call}: Fatal: Failed to downcast in {pattern}.")
@@ -69,9 +156,14 @@ impl std::fmt::Display for MockError {
             }
             Self::NoMatchingCallPatterns {
                 fn_call,
+                closest,
                 mismatches,
             } => {
-                write!(f, "{fn_call}: No matching call patterns. {mismatches}")
+                write!(f, "{fn_call}: No matching call patterns. ")?;
+                if let Some(closest) = closest {
+                    write!(f, "{closest}")?;
+                }
+                write!(f, "{mismatches}")
             }
             Self::NoOutputAvailableForCallPattern { fn_call, pattern } => {
                 write!(
@@ -107,7 +199,30 @@ impl std::fmt::Display for MockError {
             Self::CannotReturnValueMoreThanOnce { fn_call, pattern } => {
                 write!(f, "{fn_call}: Cannot return value more than once from {pattern}, because of missing Clone bound. Try using `.each_call()` or explicitly quantifying the response.")
             }
-            Self::FailedVerification(message) => write!(f, "{message}"),
+            Self::CannotBorrowFromInputs { fn_call, pattern } => {
+                write!(f, "{fn_call}: Cannot borrow the output from the inputs in {pattern}, because the output lifetime of the method is not the lifetime of all its inputs.")
+            }
+            Self::CallCountNotMatched {
+                name,
+                pattern,
+                exactness,
+                expected,
+                actual,
+            } => {
+                let exactness = match exactness {
+                    Exactness::Exact => "exactly",
+                    Exactness::AtLeast | Exactness::AtLeastPlusOne => "at least",
+                };
+                write!(f, "{name}: Expected {pattern} to match {exactness} {expected}, but it actually matched {actual}.")
+            }
+            Self::ItemsNotConsumed {
+                name,
+                pattern,
+                expected,
+                consumed,
+            } => {
+                write!(f, "{name}: Expected {expected} items returned from {pattern} to be consumed, but {consumed} were consumed.")
+            }
             Self::CannotUnmock { name } => {
                 write!(
                     f,
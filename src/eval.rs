@@ -1,22 +1,32 @@
+use crate::build::BorrowedInputs;
 use crate::call_pattern::{
-    CallPattern, DowncastResponder, DynResponder, PatIndex, PatternError, PatternResult,
+    CallPattern, DowncastResponder, DynBorrowingResponder, DynResponder, PatIndex, PatternError,
+    PatternResult,
 };
+use crate::call_tree::Outcome;
 use crate::debug;
 use crate::error::{self};
 use crate::error::{MockError, MockResult};
 use crate::fn_mocker::{FnMocker, PatternMatchMode};
-use crate::macro_api::{Evaluation, MismatchReporter};
-use crate::mismatch::Mismatches;
-use crate::output::Output;
+use crate::macro_api::{BorrowingEvaluation, Evaluation, MismatchReporter};
+use crate::mismatch::{Mismatches, Verbosity};
+use crate::output::{Output, StaticRef};
 use crate::state::SharedState;
 use crate::DynMockFn;
-use crate::{FallbackMode, MockFn};
+use crate::{FallbackMode, MockFn, Unimock};
 
 enum EvalResult<'u> {
     Responder(EvalResponder<'u>),
     Unmock,
 }
 
+// Evaluation before it is known whether the output may borrow from the inputs
+enum InnerEvaluation<'u, 'i, F: MockFn> {
+    Evaluated(<F::Output<'u> as Output<'u, F::Response>>::Type),
+    Borrowing(EvalResponder<'u>, &'u DynBorrowingResponder, F::Inputs<'i>),
+    Skipped(F::Inputs<'i>),
+}
+
 struct EvalResponder<'u> {
     fn_mocker: &'u FnMocker,
     pat_index: PatIndex,
@@ -24,74 +34,230 @@ struct EvalResponder<'u> {
 }
 
 pub(crate) fn eval<'u, 'i, F: MockFn>(
-    shared_state: &'u SharedState,
+    unimock: &'u Unimock,
     inputs: F::Inputs<'i>,
+    call_site: &'static std::panic::Location<'static>,
 ) -> MockResult<Evaluation<'u, 'i, F>> {
+    match eval_inner::<F>(unimock, inputs, call_site)? {
+        InnerEvaluation::Evaluated(output) => Ok(Evaluation::Evaluated(output)),
+        InnerEvaluation::Borrowing(eval_responder, _, inputs) => {
+            Err(MockError::CannotBorrowFromInputs {
+                fn_call: debug::FnActualCall::new(
+                    DynMockFn::new::<F>(),
+                    F::debug_inputs(&inputs),
+                    call_site,
+                ),
+                pattern: eval_responder
+                    .fn_mocker
+                    .debug_pattern(eval_responder.pat_index),
+            })
+        }
+        InnerEvaluation::Skipped(inputs) => Ok(Evaluation::Skipped(inputs)),
+    }
+}
+
+pub(crate) fn eval_borrowing<'u, 'i, F, T>(
+    unimock: &'u Unimock,
+    inputs: F::Inputs<'i>,
+    call_site: &'static std::panic::Location<'static>,
+) -> MockResult<BorrowingEvaluation<'i, F, T>>
+where
+    F: MockFn<Response = StaticRef<T>>,
+    for<'u2> F::Output<'u2>: Output<'u2, StaticRef<T>, Type = &'static T>,
+    T: ?Sized + 'static,
+{
+    match eval_inner::<F>(unimock, inputs, call_site)? {
+        InnerEvaluation::Evaluated(output) => Ok(BorrowingEvaluation::Evaluated(output)),
+        InnerEvaluation::Borrowing(eval_responder, dyn_borrowing_responder, inputs) => {
+            let borrowing_responder = match dyn_borrowing_responder.downcast::<F, T>() {
+                Ok(borrowing_responder) => borrowing_responder,
+                Err(err) => {
+                    let dyn_ctx = DynCtx {
+                        mock_fn: DynMockFn::new::<F>(),
+                        shared_state: &unimock.shared_state,
+                        input_debugger: &|| F::debug_inputs(&inputs),
+                        custom_input_debugger: &|index| F::custom_debug_input(&inputs, index),
+                        call_site,
+                    };
+                    return Err(dyn_ctx.map_pattern_error(
+                        err,
+                        eval_responder.fn_mocker,
+                        eval_responder.pat_index,
+                    ));
+                }
+            };
+
+            #[cfg(feature = "tracing")]
+            let traced_call = crate::trace_event::enabled().then(|| {
+                debug::FnActualCall::new(DynMockFn::new::<F>(), F::debug_inputs(&inputs), call_site)
+            });
+
+            let output = (borrowing_responder.func)(BorrowedInputs { inputs });
+
+            #[cfg(feature = "tracing")]
+            if let Some(fn_call) = traced_call {
+                use crate::trace_event::{self, CallOutcome};
+
+                let pattern = eval_responder
+                    .fn_mocker
+                    .debug_pattern(eval_responder.pat_index);
+                trace_event::call(&fn_call, CallOutcome::Answered(&pattern));
+            }
+
+            Ok(BorrowingEvaluation::Evaluated(output))
+        }
+        InnerEvaluation::Skipped(inputs) => Ok(BorrowingEvaluation::Skipped(inputs)),
+    }
+}
+
+fn eval_inner<'u, 'i, F: MockFn>(
+    unimock: &'u Unimock,
+    inputs: F::Inputs<'i>,
+    call_site: &'static std::panic::Location<'static>,
+) -> MockResult<InnerEvaluation<'u, 'i, F>> {
+    let shared_state = &unimock.shared_state;
     let dyn_ctx = DynCtx {
         mock_fn: DynMockFn::new::<F>(),
         shared_state,
         input_debugger: &|| F::debug_inputs(&inputs),
+        custom_input_debugger: &|index| F::custom_debug_input(&inputs, index),
+        call_site,
     };
 
-    match dyn_ctx.eval_dyn(&|pattern, reporter| pattern.match_inputs::<F>(&inputs, reporter))? {
-        EvalResult::Responder(eval_responder) => match eval_responder.dyn_responder {
-            DynResponder::Cell(dyn_cell_responder) => match dyn_ctx
-                .downcast_responder::<F, _>(dyn_cell_responder, &eval_responder)?
-                .cell
-                .try_take()
-            {
-                Some(response) => {
+    let eval_result =
+        dyn_ctx.eval_dyn(&|pattern, reporter| pattern.match_inputs::<F>(&inputs, reporter))?;
+
+    shared_state.trace_outcome(|| match &eval_result {
+        EvalResult::Responder(eval_responder)
+            if matches!(eval_responder.dyn_responder, DynResponder::Unmock) =>
+        {
+            Outcome::Unmocked
+        }
+        EvalResult::Responder(eval_responder) => Outcome::Mocked(
+            eval_responder
+                .fn_mocker
+                .debug_pattern(eval_responder.pat_index)
+                .to_string(),
+        ),
+        EvalResult::Unmock => Outcome::Unmocked,
+    });
+
+    // The event is emitted once the responder has answered, which may consume the inputs
+    #[cfg(feature = "tracing")]
+    let traced_call = crate::trace_event::enabled().then(|| {
+        let answering_pattern = match &eval_result {
+            EvalResult::Responder(eval_responder) => Some(
+                eval_responder
+                    .fn_mocker
+                    .debug_pattern(eval_responder.pat_index),
+            ),
+            EvalResult::Unmock => None,
+        };
+        (dyn_ctx.fn_call(), answering_pattern)
+    });
+
+    let evaluation = match eval_result {
+        EvalResult::Responder(eval_responder) => {
+            eval_responder.fn_mocker.call_patterns[eval_responder.pat_index.0]
+                .capture_inputs::<F>(&inputs)
+                .map_err(|err| {
+                    dyn_ctx.map_pattern_error(
+                        err,
+                        eval_responder.fn_mocker,
+                        eval_responder.pat_index,
+                    )
+                })?;
+
+            match eval_responder.dyn_responder {
+                DynResponder::Cell(dyn_cell_responder) => match dyn_ctx
+                    .downcast_responder::<F, _>(dyn_cell_responder, &eval_responder)?
+                    .cell
+                    .try_take()
+                {
+                    Some(response) => {
+                        let output = <F::Output<'u> as Output<'u, F::Response>>::from_response(
+                            *response,
+                            &shared_state.value_chain,
+                        );
+
+                        Ok(InnerEvaluation::Evaluated(output))
+                    }
+                    None => Err(MockError::CannotReturnValueMoreThanOnce {
+                        fn_call: dyn_ctx.fn_call(),
+                        pattern: eval_responder
+                            .fn_mocker
+                            .debug_pattern(eval_responder.pat_index),
+                    }),
+                },
+                DynResponder::Borrow(dyn_borrow_responder) => {
+                    let borrow_responder = dyn_ctx
+                        .downcast_responder::<F, _>(dyn_borrow_responder, &eval_responder)?;
+                    let output_result =
+                        <F::Output<'u> as Output<'u, F::Response>>::try_from_borrowed_response(
+                            &borrow_responder.borrowable,
+                        );
+
+                    match output_result {
+                        Ok(output) => Ok(InnerEvaluation::Evaluated(output)),
+                        Err(sig_err) => panic!(
+                            "BUG: Signature error in {}: {:?}",
+                            dyn_ctx.fn_call(),
+                            sig_err
+                        ),
+                    }
+                }
+                DynResponder::Function(dyn_fn_responder) => {
+                    let fn_responder =
+                        dyn_ctx.downcast_responder::<F, _>(dyn_fn_responder, &eval_responder)?;
                     let output = <F::Output<'u> as Output<'u, F::Response>>::from_response(
-                        *response,
+                        (fn_responder.func)(inputs),
                         &shared_state.value_chain,
                     );
-
-                    Ok(Evaluation::Evaluated(output))
+                    Ok(InnerEvaluation::Evaluated(output))
+                }
+                DynResponder::Instance(dyn_instance_responder) => {
+                    let instance_responder = dyn_ctx
+                        .downcast_responder::<F, _>(dyn_instance_responder, &eval_responder)?;
+                    let output = <F::Output<'u> as Output<'u, F::Response>>::from_response(
+                        (instance_responder.func)(unimock),
+                        &shared_state.value_chain,
+                    );
+                    Ok(InnerEvaluation::Evaluated(output))
                 }
-                None => Err(MockError::CannotReturnValueMoreThanOnce {
+                DynResponder::Borrowing(dyn_responder) => Ok(InnerEvaluation::Borrowing(
+                    eval_responder,
+                    dyn_responder,
+                    inputs,
+                )),
+                DynResponder::Panic(msg) => Err(MockError::ExplicitPanic {
                     fn_call: dyn_ctx.fn_call(),
                     pattern: eval_responder
                         .fn_mocker
                         .debug_pattern(eval_responder.pat_index),
+                    msg: msg.clone(),
                 }),
-            },
-            DynResponder::Borrow(dyn_borrow_responder) => {
-                let borrow_responder =
-                    dyn_ctx.downcast_responder::<F, _>(dyn_borrow_responder, &eval_responder)?;
-                let output_result =
-                    <F::Output<'u> as Output<'u, F::Response>>::try_from_borrowed_response(
-                        &borrow_responder.borrowable,
-                    );
-
-                match output_result {
-                    Ok(output) => Ok(Evaluation::Evaluated(output)),
-                    Err(sig_err) => panic!(
-                        "BUG: Signature error in {}: {:?}",
-                        dyn_ctx.fn_call(),
-                        sig_err
-                    ),
-                }
+                DynResponder::Unmock => Ok(InnerEvaluation::Skipped(inputs)),
             }
-            DynResponder::Function(dyn_fn_responder) => {
-                let fn_responder =
-                    dyn_ctx.downcast_responder::<F, _>(dyn_fn_responder, &eval_responder)?;
-                let output = <F::Output<'u> as Output<'u, F::Response>>::from_response(
-                    (fn_responder.func)(inputs),
-                    &shared_state.value_chain,
-                );
-                Ok(Evaluation::Evaluated(output))
+        }
+        EvalResult::Unmock => Ok(InnerEvaluation::Skipped(inputs)),
+    };
+    let evaluation = evaluation?;
+
+    #[cfg(feature = "tracing")]
+    if let Some((fn_call, answering_pattern)) = traced_call {
+        use crate::trace_event::{self, CallOutcome};
+
+        match (&evaluation, answering_pattern) {
+            (InnerEvaluation::Evaluated(_), Some(pattern)) => {
+                trace_event::call(&fn_call, CallOutcome::Answered(&pattern))
             }
-            DynResponder::Panic(msg) => Err(MockError::ExplicitPanic {
-                fn_call: dyn_ctx.fn_call(),
-                pattern: eval_responder
-                    .fn_mocker
-                    .debug_pattern(eval_responder.pat_index),
-                msg: msg.clone(),
-            }),
-            DynResponder::Unmock => Ok(Evaluation::Skipped(inputs)),
-        },
-        EvalResult::Unmock => Ok(Evaluation::Skipped(inputs)),
+            (InnerEvaluation::Skipped(_), _) => trace_event::call(&fn_call, CallOutcome::Unmocked),
+            // Borrowing responders have not answered yet, see `eval_borrowing`
+            _ => {}
+        }
     }
+
+    Ok(evaluation)
 }
 
 /// 'u = unimock instance, 's = stack
@@ -99,6 +265,8 @@ struct DynCtx<'u, 's> {
     mock_fn: DynMockFn,
     shared_state: &'u SharedState,
     input_debugger: &'s dyn Fn() -> String,
+    custom_input_debugger: &'s dyn Fn(usize) -> Option<String>,
+    call_site: &'static std::panic::Location<'static>,
 }
 
 impl<'u, 's> DynCtx<'u, 's> {
@@ -133,15 +301,44 @@ impl<'u, 's> DynCtx<'u, 's> {
             },
             None => match self.shared_state.fallback_mode {
                 FallbackMode::Error => {
-                    let mut mismatches = Mismatches::new();
+                    let mut mismatches = Mismatches::new(
+                        *self.shared_state.diff_mode.lock().unwrap(),
+                        *self.shared_state.output_limits.lock().unwrap(),
+                    );
                     for (pat_index, call_pattern) in fn_mocker.call_patterns.iter().enumerate() {
                         let mut mismatch_reporter = MismatchReporter::new_enabled();
                         let _ = match_inputs(call_pattern, Some(&mut mismatch_reporter));
-                        mismatches.collect_from_reporter(PatIndex(pat_index), mismatch_reporter);
+                        mismatches.collect_from_reporter(
+                            PatIndex(pat_index),
+                            fn_mocker.debug_pattern(PatIndex(pat_index)).location(),
+                            fn_mocker.dyn_mock_fn.input_names,
+                            mismatch_reporter,
+                        );
                     }
+                    mismatches.apply_custom_debug(self.custom_input_debugger);
+
+                    let pattern_count = fn_mocker.call_patterns.len();
+                    let closest = match mismatches.closest_pattern() {
+                        Some(closest) if pattern_count > 1 => {
+                            let omitted_patterns =
+                                match *self.shared_state.verbosity.lock().unwrap() {
+                                    Verbosity::Concise => mismatches.retain_closest(&closest),
+                                    Verbosity::Verbose => 0,
+                                };
+                            Some(Box::new(debug::ClosestPatternDebug {
+                                pat_index: closest.pat_index,
+                                pattern: fn_mocker.debug_pattern(closest.pat_index),
+                                differing_inputs: closest.differing_inputs,
+                                guard_failed: closest.guard_failed,
+                                omitted_patterns,
+                            }))
+                        }
+                        _ => None,
+                    };
 
                     Err(MockError::NoMatchingCallPatterns {
                         fn_call: self.fn_call(),
+                        closest,
                         mismatches,
                     })
                 }
@@ -188,8 +385,17 @@ impl<'u, 's> DynCtx<'u, 's> {
                 if !match_inputs(pattern, Some(&mut mismatch_reporter))
                     .map_err(|err| self.map_pattern_error(err, fn_mocker, pat_index))?
                 {
-                    let mut mismatches = Mismatches::new();
-                    mismatches.collect_from_reporter(pat_index, mismatch_reporter);
+                    let mut mismatches = Mismatches::new(
+                        *self.shared_state.diff_mode.lock().unwrap(),
+                        *self.shared_state.output_limits.lock().unwrap(),
+                    );
+                    mismatches.collect_from_reporter(
+                        pat_index,
+                        fn_mocker.debug_pattern(pat_index).location(),
+                        fn_mocker.dyn_mock_fn.input_names,
+                        mismatch_reporter,
+                    );
+                    mismatches.apply_custom_debug(self.custom_input_debugger);
 
                     return Err(MockError::InputsNotMatchedInCallOrder {
                         fn_call: self.fn_call(),
@@ -238,10 +444,22 @@ impl<'u, 's> DynCtx<'u, 's> {
     }
 
     fn fn_call(&self) -> debug::FnActualCall {
-        debug::FnActualCall {
-            mock_fn: self.mock_fn.clone(),
-            inputs_debug: self.debug_inputs(),
-        }
+        let max_input_len = self
+            .shared_state
+            .output_limits
+            .lock()
+            .unwrap()
+            .max_input_len;
+        let inputs_debug = self.debug_inputs();
+        // keep the parentheses around the truncated inputs
+        let inputs_debug = match inputs_debug
+            .strip_prefix('(')
+            .and_then(|inputs| inputs.strip_suffix(')'))
+        {
+            Some(inputs) => format!("({})", debug::truncate(inputs, max_input_len)),
+            None => debug::truncate(&inputs_debug, max_input_len).into_owned(),
+        };
+        debug::FnActualCall::new(self.mock_fn.clone(), inputs_debug, self.call_site)
     }
 
     fn debug_inputs(&self) -> String {
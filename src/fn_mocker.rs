@@ -55,6 +55,14 @@ impl FnMocker {
                     errors,
                 )
                 .0;
+
+            for item_consumption in &pattern.item_consumptions {
+                item_consumption.verify(
+                    self.dyn_mock_fn.name,
+                    || self.debug_pattern(PatIndex(pat_index)),
+                    errors,
+                );
+            }
         }
 
         if total_calls == 0 {
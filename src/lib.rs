@@ -295,12 +295,20 @@
 //! * Traits with generic parameters, although these cannot be lifetime constrained (i.e. need to satisfy `T: 'static`).
 //! * Methods with any self receiver (`self`, `&self`, `&mut self` or arbitrary (e.g. `self: Rc<Self>`)).
 //! * Methods that take reference inputs.
+//! * Methods with destructuring parameter patterns, like `(x, y): (i32, i32)`.
 //! * Methods returning references to self.
+//! * Methods returning `Self`, `Box<Self>` or `Arc<Self>`.
+//! * Methods returning other mocked objects, e.g. `Box<dyn Trait>`, using child mocks (see `returns_mock`).
+//! * Methods returning boxed iterators like `Box<dyn Iterator<Item = T>>`, or boxed streams when the `futures` feature is enabled (see `returns_items`).
 //! * Methods returning references to arguments.
 //! * Methods returning `Option<&T>`, `Result<&T, E>` or `Vec<&T>` for any `T` that is borrowed from `self`.
+//! * Methods returning nested combinations of `Option`, `Vec`, `Result`, `HashMap`, `BTreeMap` and tuples containing self-borrowed data, e.g. `Result<Vec<&T>, E>`.
+//!   Other output shapes can be supported by implementing the traits in the [output](crate::output) module.
 //! * Methods returning any tuple combination of self-borrowed or owned elements up to 4 elements.
-//! * Methods returning data borrowed from non-self arguments (these have to be converted to static reference, e.g. via [`Box::leak`](Box::leak)).
+//! * Methods returning data borrowed from non-self arguments. When the output lifetime is the lifetime of all the non-self inputs, the output can be borrowed from the inputs using `answers_borrowing`.
+//!   Otherwise it has to be converted to a static reference, e.g. via [`Box::leak`](Box::leak).
 //! * Methods returning a type containing lifetime parameters. For a mocked return they will have to be `'static`.
+//! * Methods taking callbacks as `impl Fn*` arguments. These are passed to unimock as `Box<dyn Fn*>`, so responders like `invokes_arg` (or `answers`) are able to call them. Unmock functions receive the boxed callback.
 //! * Generic methods using either explicit generic params or argument-position `impl Trait`.
 //! * Async methods when the trait is annotated with `#[async_trait]`.
 //! * Methods that return a future that is an associated type. Requires nightly.
@@ -309,7 +317,13 @@
 //! * Traits with associated types. Unimock would have to select a type at random, which does not make a lot of sense.
 //! * Static methods, i.e. no `self` receiver. Static methods with a _default body_ are accepted though, but not mockable.
 //! * Methods receiving `&mut` arguments other than `&mut self`.
-//!     It _might_ work, but is currently unsupported due to stricter lifetime constraints that is harder to express via generics.
+//!   It _might_ work, but is currently unsupported due to stricter lifetime constraints that is harder to express via generics.
+//!
+//! #### Logging mock interactions with `tracing`
+//! When the `tracing` feature is enabled, every call into a [Unimock] instance emits a `DEBUG` event with the target `unimock`.
+//! The event records the function name, the rendered inputs, the call pattern that answered the call (if any),
+//! and the outcome: `answered`, `unmocked` or `failed`.
+//! Mock interactions then show up inline with the logs of the code under test, when a `tracing` subscriber is installed.
 //!
 //! ### Selecting a name for the mock `api`
 //! Due to [macro hygiene](https://en.wikipedia.org/wiki/Hygienic_macro),
@@ -362,24 +376,33 @@
 pub mod build;
 /// APIs used by macros, not intended to be used directly.
 pub mod macro_api;
+/// Reusable argument matchers for the [matching] macro.
+pub mod matchers;
 /// Function outputs.
 pub mod output;
 /// Traits and types used for describing the properties of various mock types.
 pub mod property;
-#[doc(hidden)]
+/// Storage for values that function outputs borrow from.
 pub mod value_chain;
 
 mod assemble;
 mod call_pattern;
+mod call_tree;
+mod captor;
 mod cell;
 mod clause;
+mod closure;
 mod counter;
 mod debug;
 mod error;
 mod eval;
 mod fn_mocker;
 mod mismatch;
+mod report;
 mod state;
+mod structural_diff;
+#[cfg(feature = "tracing")]
+mod trace_event;
 
 use std::any::TypeId;
 use std::sync::Arc;
@@ -388,6 +411,11 @@ use assemble::MockAssembler;
 use call_pattern::DynInputMatcher;
 use macro_api::Matching;
 
+pub use captor::Captor;
+pub use closure::MockClosure;
+pub use mismatch::{DiffMode, Verbosity};
+pub use report::ReportTarget;
+
 ///
 /// Autogenerate mocks for all methods in the annotated traits, and `impl` it for [Unimock].
 ///
@@ -481,11 +509,69 @@ use macro_api::Matching;
 ///
 /// * `#[unimock(api=#ident)]`: Export a mocking API as a module with the given name
 /// * `#[unimock(api=[method1, method2, ..])`: Instead of generating a module, generate top-level mock structs for the methods in the trait,
-///     with the names of those structs passed with array-like syntax in the same order as the methods appear in the trait definition.
+///   with the names of those structs passed with array-like syntax in the same order as the methods appear in the trait definition.
 /// * `#[unimock(unmock_with=[a, b, _])`: Given there are e.g. 3 methods in the annotated trait, uses the given paths as unmock implementations.
-///     The functions are assigned to the methods in the same order as the methods are listed in the trait.
-///     A value of `_` means _no unmock support_ for that method.
+///   The functions are assigned to the methods in the same order as the methods are listed in the trait.
+///   A value of `_` means _no unmock support_ for that method.
 /// * `#[unimock(prefix=path)]`: Makes unimock use a different path prefix than `::unimock`, in case the crate has been re-exported through another crate.
+///
+/// # Method arguments
+/// Individual trait methods may also be annotated with `#[unimock(..)]`, which avoids positional lists at the trait level:
+///
+/// * `#[unimock(unmock_with=path)]` or `#[unimock(unmock_with=path(self, a))]`: Use the given function as unmock implementation for this method.
+///   Cannot be combined with a non-`_` entry for the same method in the trait-level `unmock_with`.
+/// * `#[unimock(skip)]`: Do not mock this method. The method must have a default body, which [Unimock] will use.
+/// * `#[unimock(name=ident)]`: Use the given name for the method's [MockFn] instead of the method name (or the name from the trait-level `api=[..]` list).
+///
+/// # Parameter arguments
+/// Method parameters may be annotated with `#[unimock(..)]` to control how the input is rendered in error messages and mismatch reports:
+///
+/// * `#[unimock(debug_with=path)]`: Render the input using the given function, which receives a reference to the (dereferenced) input and returns a [String].
+///   The expected values of `eq!` and `ne!` are rendered by the same function, other expected values of the input are not shown in mismatch reports.
+/// * `#[unimock(redact)]`: Render the input as `<redacted>`, e.g. for passwords and tokens. Mismatch reports of a redacted input show neither its actual nor its expected value.
+///
+/// ```rust
+/// # use unimock::*;
+/// #[unimock(api=LoginMock)]
+/// trait Login {
+///     fn login(&self, #[unimock(redact)] password: &str, #[unimock(debug_with=short)] id: u64) -> bool;
+/// }
+///
+/// fn short(id: &u64) -> String {
+///     format!("#{}", id % 1000)
+/// }
+///
+/// let result = std::panic::catch_unwind(|| {
+///     Unimock::new(()).login("hunter2", 12345);
+/// });
+/// let msg = result.unwrap_err().downcast::<String>().unwrap();
+/// assert!(msg.starts_with("Login::login(password: <redacted>, id: #345): No mock implementation found."));
+/// ```
+///
+/// ```rust
+/// # use unimock::*;
+/// #[unimock(api=TraitMock)]
+/// trait Trait {
+///     #[unimock(unmock_with=real_double)]
+///     fn double(&self, a: i32) -> i32;
+///
+///     #[unimock(name=greeting)]
+///     fn hello(&self) -> String;
+///
+///     #[unimock(skip)]
+///     fn provided(&self) -> String {
+///         self.hello()
+///     }
+/// }
+///
+/// fn real_double(_: &impl std::any::Any, a: i32) -> i32 {
+///     a * 2
+/// }
+///
+/// let u = Unimock::new_partial(TraitMock::greeting.next_call(matching!()).returns("hi"));
+/// assert_eq!(4, u.double(2));
+/// assert_eq!("hi", u.provided());
+/// ```
 pub use unimock_macros::unimock;
 
 ///
@@ -571,6 +657,29 @@ pub use unimock_macros::unimock;
 ///
 /// Internally it works by calling [macro_api::as_str_ref] on inputs matched by a string literal.
 ///
+/// # Matching inputs by name
+///
+/// Inputs can be matched by the names of the trait method's parameters, by prefixing each pattern with `name:`.
+/// Inputs that are not named are not matched.
+/// A trailing `..` ignores the remaining inputs, and can also be used with positional patterns.
+/// Mismatched named inputs are reported by name in the diagnostics.
+/// Inputs of generic methods can only be matched by position.
+///
+/// ```rust
+/// # use unimock::*;
+/// #[unimock(api=Mock)]
+/// trait Trait {
+///     fn update(&self, tenant: &str, user_id: u32, name: String, active: bool) -> bool;
+/// }
+///
+/// fn args(_: &dyn Fn(&mut macro_api::Matching<Mock::update>)) {}
+///
+/// args(matching!(user_id: 42, ..));
+/// args(matching!(name: "alice", active: true));
+/// args(matching!("acme", 42, ..));
+/// args(matching!(user_id: id, .. if *id > 10));
+/// ```
+///
 /// # Matching using `Eq`
 ///
 /// Since patterns in Rust are somewhat limited, the matching macro also supports matching using [Eq](std::cmp::Eq).
@@ -604,6 +713,58 @@ pub use unimock_macros::unimock;
 /// assert_eq!("other", <Unimock as Trait>::func(&u, Data(vec![0])));
 /// ```
 ///
+/// # Matching using matchers
+///
+/// A single argument can also be matched by a [Matcher](crate::matchers::Matcher), by enclosing it within `is!(_)`.
+/// Mismatched matchers describe themselves in the diagnostics.
+/// See the [matchers] module for the available matchers.
+///
+/// ```rust
+/// # use unimock::*;
+/// use unimock::matchers::*;
+///
+/// #[unimock(api=Mock)]
+/// trait Trait {
+///     fn func(&self, path: &str, scale: f32) -> &str;
+/// }
+///
+/// let u = Unimock::new((
+///     Mock::func
+///         .each_call(matching!(is!(starts_with("/tmp")), is!(approx(1.0, 0.01))))
+///         .returns("temporary"),
+///     Mock::func
+///         .each_call(matching!(_, _))
+///         .returns("other")
+/// ));
+///
+/// assert_eq!("temporary", <Unimock as Trait>::func(&u, "/tmp/file", 0.999));
+/// assert_eq!("other", <Unimock as Trait>::func(&u, "/tmp/file", 2.0));
+/// ```
+///
+/// # Matching using regular expressions
+///
+/// When the `regex` feature is enabled, a string-like argument can be matched by a regular expression by enclosing it within `regex!(_)`.
+/// The regex is compiled once per call pattern, and an invalid regex panics when the call pattern is set up.
+///
+/// ```rust
+/// # #[cfg(feature = "regex")]
+/// # {
+/// # use unimock::*;
+/// #[unimock(api=Mock)]
+/// trait Trait {
+///     fn query(&self, sql: &str) -> i32;
+/// }
+///
+/// let u = Unimock::new(
+///     Mock::query
+///         .each_call(matching!(regex!("^SELECT .* FROM users")))
+///         .returns(1)
+/// );
+///
+/// assert_eq!(1, <Unimock as Trait>::query(&u, "SELECT name FROM users"));
+/// # }
+/// ```
+///
 pub use unimock_macros::matching;
 
 #[derive(Clone, Copy)]
@@ -638,6 +799,9 @@ enum FallbackMode {
 /// Unimock will also induce a panic if the original instance gets dropped in a thread that does not equal the creator thread.
 /// Therefore, Unimock should always be cloned before sending off to another thread.
 ///
+/// Instances returned from mocked methods through [`returns_self`](build::DefineResponse::returns_self) are an exception to this rule:
+///     they count as original instances, and verification runs when the last original instance is dropped.
+///
 pub struct Unimock {
     original_instance: bool,
     shared_state: Arc<state::SharedState>,
@@ -702,6 +866,121 @@ impl Unimock {
         )
     }
 
+    /// Configure how mismatched values are compared in the mismatch reports of this instance.
+    ///
+    /// # Example
+    /// ```rust,should_panic
+    /// # use unimock::*;
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Item {
+    ///     pub name: &'static str,
+    ///     pub price: u32,
+    /// }
+    ///
+    /// #[unimock(api=ShopMock)]
+    /// trait Shop {
+    ///     fn buy(&self, items: Vec<Item>);
+    /// }
+    ///
+    /// let u = Unimock::new(
+    ///     ShopMock::buy
+    ///         .each_call(matching!(eq!(&vec![Item { name: "apple", price: 12 }])))
+    ///         .returns(())
+    /// )
+    /// .with_diff_mode(DiffMode::Structural);
+    ///
+    /// // Panics, reporting `[0].price: 10 != 12` instead of the complete values:
+    /// u.buy(vec![Item { name: "apple", price: 10 }]);
+    /// ```
+    pub fn with_diff_mode(self, diff_mode: DiffMode) -> Self {
+        *self.shared_state.diff_mode.lock().unwrap() = diff_mode;
+        self
+    }
+
+    /// Limit the length in bytes of rendered inputs in the error messages of this instance.
+    ///
+    /// This applies to the inputs of the actual call, and to each value in mismatch reports.
+    /// Values are also truncated before they get diffed, which keeps the diff computation bounded.
+    /// Elided parts are marked, e.g. `… 48213 more bytes`. The default limit is 4 KiB.
+    pub fn with_max_input_len(self, max_input_len: usize) -> Self {
+        self.shared_state
+            .output_limits
+            .lock()
+            .unwrap()
+            .max_input_len = max_input_len;
+        self
+    }
+
+    /// Limit the length in bytes of each rendered diff in the mismatch reports of this instance.
+    ///
+    /// Diffs are preferably cut at the end of a line.
+    /// Elided parts are marked, e.g. `… 48213 more bytes`. The default limit is 16 KiB.
+    pub fn with_max_diff_len(self, max_diff_len: usize) -> Self {
+        self.shared_state.output_limits.lock().unwrap().max_diff_len = max_diff_len;
+        self
+    }
+
+    /// Write a machine-readable report of every error of this instance to the given target.
+    ///
+    /// Each error and verification failure is written as one line of JSON,
+    /// containing e.g. the function name, inputs, pattern location, expected and actual call counts and mismatches.
+    /// Reports are written when the instance panics or gets verified.
+    ///
+    /// Reports can also be enabled for all instances by setting the `UNIMOCK_REPORT` environment variable
+    /// to `stderr` or to the path of a file that reports get appended to.
+    pub fn with_report(self, target: ReportTarget) -> Self {
+        self.shared_state.set_report_target(Some(target));
+        self
+    }
+
+    /// Record the tree of calls into this instance, which gets printed when the instance panics.
+    ///
+    /// In [Unimock::new_partial] mode, unmocked functions may call back into the instance,
+    /// so a single top-level call can fan out into a tree of mocked and unmocked calls.
+    /// Each call in the tree is shown with the call pattern that answered it, or as unmocked,
+    /// together with its output when the output type implements [Debug](std::fmt::Debug).
+    ///
+    /// Every call is nested under the call it was made from, also when calls are made concurrently from several threads or futures.
+    /// At most 1000 calls are recorded, and inputs and outputs are truncated like in mismatch reports, see [with_max_input_len](Self::with_max_input_len).
+    ///
+    /// ```rust,should_panic
+    /// # use unimock::*;
+    /// #[unimock(api=FactorialMock, unmock_with=[my_factorial(self, input)])]
+    /// trait Factorial {
+    ///     fn factorial(&self, input: u32) -> u32;
+    /// }
+    ///
+    /// fn my_factorial(f: &impl Factorial, input: u32) -> u32 {
+    ///     f.factorial(input - 1) * input
+    /// }
+    ///
+    /// let u = Unimock::new(FactorialMock::factorial.stub(|each| {
+    ///     each.call(matching!(0)).returns(1_u32);
+    ///     each.call(matching!(3 | 2)).unmocked();
+    /// }))
+    /// .with_call_tree();
+    ///
+    /// // Panics, since `factorial(1)` is not mocked. The call tree shows the unmocked calls leading up to it:
+    /// //   Factorial::factorial(input: 3) [unmocked]
+    /// //     Factorial::factorial(input: 2) [unmocked]
+    /// //       Factorial::factorial(input: 1) [failed]
+    /// u.factorial(3);
+    /// ```
+    pub fn with_call_tree(self) -> Self {
+        self.shared_state.enable_call_tree();
+        self
+    }
+
+    /// Configure how much detail is included in the mismatch reports of this instance.
+    ///
+    /// By default, when a call matches none of the call patterns of a [MockFn],
+    /// only the mismatches of the closest call pattern are reported.
+    /// With [Verbosity::Verbose], the mismatches of every call pattern are reported.
+    pub fn with_verbosity(self, verbosity: Verbosity) -> Self {
+        *self.shared_state.verbosity.lock().unwrap() = verbosity;
+        self
+    }
+
     #[track_caller]
     fn from_assembler(
         assembler_result: Result<MockAssembler, String>,
@@ -712,9 +991,12 @@ impl Unimock {
             Err(error) => panic!("{error}"),
         };
 
+        let shared_state = Arc::new(state::SharedState::new(fn_mockers, fallback_mode));
+        shared_state.register_children();
+
         Self {
             original_instance: true,
-            shared_state: Arc::new(state::SharedState::new(fn_mockers, fallback_mode)),
+            shared_state,
         }
     }
 
@@ -727,6 +1009,25 @@ impl Unimock {
     }
 }
 
+impl Unimock {
+    /// Construct a child mock, which is verified by the parent it gets handed out from.
+    #[track_caller]
+    pub(crate) fn new_child(setup: impl Clause) -> Self {
+        let mut child = Self::new(setup);
+        child.original_instance = false;
+        child
+    }
+
+    /// Create a new instance sharing state with this one, which also takes part in verification.
+    pub(crate) fn successor(&self) -> Unimock {
+        self.shared_state.add_original_instance();
+        Unimock {
+            original_instance: true,
+            shared_state: self.shared_state.clone(),
+        }
+    }
+}
+
 impl Clone for Unimock {
     fn clone(&self) -> Unimock {
         Unimock {
@@ -743,6 +1044,11 @@ impl Drop for Unimock {
             return;
         }
 
+        // verification is performed by the last original instance to be dropped.
+        if self.shared_state.release_original_instance() > 0 {
+            return;
+        }
+
         // skip verification if already panicking in the original thread.
         if std::thread::panicking() {
             return;
@@ -759,25 +1065,26 @@ impl Drop for Unimock {
         }
 
         #[track_caller]
-        fn panic_if_nonempty(errors: &[error::MockError]) {
-            if errors.is_empty() {
+        fn panic_if_nonempty(error_strings: &[String]) {
+            if error_strings.is_empty() {
                 return;
             }
 
-            let error_strings = errors.iter().map(|err| err.to_string()).collect::<Vec<_>>();
             panic!("{}", error_strings.join("\n"));
         }
 
         {
             // if already panicked, it must be in another thread. Forward that panic to the original thread.
             // (if original is even still in the original thread.. But panic as close to the test "root" as possible)
-            let panic_reasons = self.shared_state.clone_panic_reasons();
+            let mut panic_reasons = Vec::new();
+            self.shared_state.collect_panic_reasons(&mut panic_reasons);
             panic_if_nonempty(&panic_reasons);
         }
 
         let mut mock_errors = Vec::new();
-        for (_, fn_mocker) in self.shared_state.fn_mockers.iter() {
-            fn_mocker.verify(&mut mock_errors);
+        self.shared_state.verify(&mut mock_errors);
+        if !mock_errors.is_empty() {
+            mock_errors.extend(self.shared_state.call_tree_string());
         }
         panic_if_nonempty(&mock_errors);
     }
@@ -832,9 +1139,32 @@ pub trait MockFn: Sized + 'static {
     /// The name to use for runtime errors.
     const NAME: &'static str;
 
+    /// The names of the function's parameters, in the same order as the inputs.
+    ///
+    /// Parameters declared with a destructuring pattern have no name, and are represented by an empty string.
+    /// Without names, the inputs are rendered positionally in error messages.
+    const INPUT_NAMES: &'static [&'static str] = &[];
+
     /// Compute some debug representation of the inputs.
     fn debug_inputs(inputs: &Self::Inputs<'_>) -> String;
 
+    /// Compute the debug representation of the input at the given index.
+    ///
+    /// Returns [None] for inputs that can't be rendered because their type does not implement [Debug](std::fmt::Debug).
+    fn debug_input(inputs: &Self::Inputs<'_>, index: usize) -> Option<String> {
+        let _ = (inputs, index);
+        None
+    }
+
+    /// Compute a custom debug representation of the input at the given index,
+    /// for parameters annotated with `#[unimock(debug_with=..)]` or `#[unimock(redact)]`.
+    ///
+    /// Returns [None] for inputs that are rendered by their [Debug](std::fmt::Debug) implementation.
+    fn custom_debug_input(inputs: &Self::Inputs<'_>, index: usize) -> Option<String> {
+        let _ = (inputs, index);
+        None
+    }
+
     /// Create a stubbing clause by grouping calls.
     ///
     /// A stub sets up call patterns on a single function, that can be matched in any order.
@@ -859,6 +1189,7 @@ pub trait MockFn: Sized + 'static {
     ///
     /// This call pattern variant supports return values that do not implement [Clone],
     /// therefore the call pattern can only be matched a single time.
+    #[track_caller]
     fn some_call(
         self,
         matching_fn: &dyn Fn(&mut Matching<Self>),
@@ -876,6 +1207,7 @@ pub trait MockFn: Sized + 'static {
     /// that needs to be specified on this MockFn.
     ///
     /// This variant is specialized for functions called multiple times.
+    #[track_caller]
     fn each_call(
         self,
         matching_fn: &dyn Fn(&mut Matching<Self>),
@@ -892,6 +1224,7 @@ pub trait MockFn: Sized + 'static {
     /// This differens from [MockFn::stub], in that that a stub defines all call patterns without any
     /// specific required call order. This function takes only single input matcher, that MUST be
     /// matched in the order specified, relative to other next calls.
+    #[track_caller]
     fn next_call(
         self,
         matching_fn: &dyn Fn(&mut Matching<Self>),
@@ -950,6 +1283,7 @@ impl<T: clause::ClauseSealed> Clause for T {}
 pub(crate) struct DynMockFn {
     type_id: TypeId,
     name: &'static str,
+    input_names: &'static [&'static str],
 }
 
 impl DynMockFn {
@@ -957,10 +1291,52 @@ impl DynMockFn {
         Self {
             type_id: TypeId::of::<F>(),
             name: F::NAME,
+            input_names: F::INPUT_NAMES,
         }
     }
 }
 
-// Hidden responder wrapper used in the Respond/RespondOnce traits hidden methods
-#[doc(hidden)]
+/// A type-erased responder, produced by [IntoResponseOnce](output::IntoResponseOnce) and [IntoResponseClone](output::IntoResponseClone).
+///
+/// Custom output shapes construct responders using the associated functions of this type.
 pub struct Responder(call_pattern::DynResponder);
+
+impl Responder {
+    /// A responder that can respond only once with the given response.
+    pub fn once<F: MockFn>(response: <F::Response as output::Respond>::Type) -> Self
+    where
+        <F::Response as output::Respond>::Type: Send + Sync,
+    {
+        Self(call_pattern::DynResponder::new_cell::<F>(response))
+    }
+
+    /// A responder that responds with a clone of the given response every time.
+    pub fn cloned<F: MockFn>(response: <F::Response as output::Respond>::Type) -> Self
+    where
+        <F::Response as output::Respond>::Type: Clone + Send + Sync,
+    {
+        Self(call_pattern::DynResponder::new_clone_cell::<F>(response))
+    }
+
+    /// A responder that produces a new response every time, using the given function.
+    pub fn factory<F: MockFn>(
+        factory: impl Fn() -> <F::Response as output::Respond>::Type + Send + Sync + 'static,
+    ) -> Self
+    where
+        <F::Response as output::Respond>::Type: Send + Sync,
+    {
+        Self(call_pattern::DynResponder::new_clone_factory_cell::<F>(
+            move || Some(factory()),
+        ))
+    }
+
+    /// A responder that keeps the response inside Unimock, and lends it out every time.
+    ///
+    /// The output is produced using [Output::try_from_borrowed_response](output::Output::try_from_borrowed_response).
+    pub fn borrowed<F: MockFn>(response: <F::Response as output::Respond>::Type) -> Self
+    where
+        <F::Response as output::Respond>::Type: Send + Sync,
+    {
+        Self(call_pattern::DynResponder::new_borrow::<F>(response))
+    }
+}
@@ -1,9 +1,12 @@
 use crate::call_pattern::InputIndex;
+use crate::call_tree::CurrentCalls;
 use crate::debug::{self, filter_questionmark};
 use crate::mismatch::{Mismatch, MismatchKind};
 use crate::output::Output;
 use crate::{call_pattern::MatchingFn, call_pattern::MatchingFnDebug, *};
 
+pub use crate::call_tree::TraceFuture;
+
 /// The evaluation of a [MockFn].
 ///
 /// Used to tell trait implementations whether to do perform their own evaluation of a call.
@@ -32,24 +35,53 @@ impl<'u, 'i, F: MockFn> Evaluation<'u, 'i, F> {
     }
 }
 
+/// The evaluation of a [MockFn] whose output is a reference borrowed from its inputs.
+///
+/// Unlike [Evaluation], the output lifetime is tied to the inputs instead of the unimock instance.
+pub enum BorrowingEvaluation<'i, F: MockFn, T: ?Sized> {
+    /// Function evaluated to its output.
+    Evaluated(&'i T),
+    /// Function not yet evaluated.
+    Skipped(F::Inputs<'i>),
+}
+
+impl<'i, F: MockFn, T: ?Sized> BorrowingEvaluation<'i, F, T> {
+    /// Unwrap the `Evaluated` variant, or panic.
+    /// The unimock instance must be passed in order to register that an eventual panic happened.
+    pub fn unwrap(self, unimock: &Unimock) -> &'i T {
+        match self {
+            Self::Evaluated(output) => output,
+            Self::Skipped(_) => panic!(
+                "{}",
+                unimock
+                    .shared_state
+                    .prepare_panic(error::MockError::CannotUnmock { name: F::NAME })
+            ),
+        }
+    }
+}
+
 /// A builder for argument matchers.
 pub struct Matching<F: MockFn> {
     pub(crate) mock_fn: std::marker::PhantomData<F>,
     pub(crate) matching_fn: Option<MatchingFn<F>>,
     pub(crate) matching_fn_debug: Option<MatchingFnDebug<F>>,
     pub(crate) matcher_debug: Option<debug::InputMatcherDebug>,
+    pub(crate) location: &'static std::panic::Location<'static>,
 }
 
 impl<F> Matching<F>
 where
     F: MockFn,
 {
+    #[track_caller]
     pub(crate) fn new() -> Self {
         Self {
             mock_fn: std::marker::PhantomData,
             matching_fn: None,
             matching_fn_debug: None,
             matcher_debug: None,
+            location: std::panic::Location::caller(),
         }
     }
 
@@ -82,11 +114,168 @@ where
     /// This way, a mismatch may be easier to debug, as the debug info can be printed as part of panic messages.
     pub fn pat_debug(&mut self, pat_debug: &'static str, file: &'static str, line: u32) {
         self.matcher_debug = Some(debug::InputMatcherDebug {
-            pat_debug,
+            pat_debug: pat_debug.into(),
             file,
             line,
         });
     }
+
+    /// Register a computed debug string for the pattern, located where the call pattern was defined.
+    pub(crate) fn pat_debug_at_location(&mut self, pat_debug: String) {
+        self.matcher_debug = Some(debug::InputMatcherDebug {
+            pat_debug: pat_debug.into(),
+            file: self.location.file(),
+            line: self.location.line(),
+        });
+    }
+}
+
+/// Access to the inputs of a [MockFn] by parameter name.
+///
+/// Used by the [matching] macro for patterns like `matching!(user_id: 42, ..)`.
+pub struct InputNames<F: MockFn>(std::marker::PhantomData<fn(F)>);
+
+impl<F: MockFn> InputNames<F> {
+    /// Input names of the MockFn being matched.
+    pub fn of(_: &Matching<F>) -> Self {
+        Self(std::marker::PhantomData)
+    }
+
+    /// The names of all the inputs.
+    pub fn names(self) -> &'static [&'static str] {
+        F::INPUT_NAMES
+    }
+
+    /// The index of the input with the given name.
+    pub fn index(self, name: &str) -> usize {
+        F::INPUT_NAMES
+            .iter()
+            .position(|input_name| *input_name == name)
+            .unwrap_or_else(|| panic!("{} has no input named `{name}`", F::NAME))
+    }
+
+    /// Borrow the inputs by name.
+    pub fn borrow<'a, 'i>(self, inputs: &'a F::Inputs<'i>) -> F::Named<'a, 'i>
+    where
+        F: NamedInputs,
+        'i: 'a,
+    {
+        F::named_inputs(inputs)
+    }
+
+    /// Borrow the first input, for patterns like `matching!(42, ..)` which don't know the number of inputs.
+    pub fn first<'a, 'i>(self, inputs: &'a F::Inputs<'i>) -> &'a F::First<'i>
+    where
+        F: FirstInput,
+    {
+        F::first_input(inputs)
+    }
+}
+
+impl<F: MockFn> Clone for InputNames<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: MockFn> Copy for InputNames<F> {}
+
+/// A [MockFn] whose inputs can be borrowed by the names of the function's parameters.
+///
+/// Implemented by `#[unimock]` for non-generic methods, and used by the [matching] macro for patterns like `matching!(user_id: 42, ..)`.
+pub trait NamedInputs: MockFn {
+    /// The inputs borrowed by name.
+    type Named<'a, 'i>
+    where
+        'i: 'a;
+
+    /// Borrow the inputs by the names of the function's parameters.
+    fn named_inputs<'a, 'i>(inputs: &'a Self::Inputs<'i>) -> Self::Named<'a, 'i>
+    where
+        'i: 'a;
+}
+
+/// A [MockFn] with inputs of type `T` annotated with `#[unimock(debug_with=..)]`.
+///
+/// Implemented by `#[unimock]`, and used by the [matching] macro to render the expected values of `eq!` and `ne!`
+/// with the same function as the input.
+pub trait DebugExpected<T: ?Sized>: MockFn {
+    /// Render the expected value of the input at the given index, or [None] if that input is not of type `T`.
+    fn debug_expected(index: usize, expected: &T) -> Option<String>;
+}
+
+/// Rendering of expected values by the custom debug functions of a [MockFn].
+///
+/// Used by the [matching] macro, see [DebugExpected].
+pub struct CustomDebug<F: MockFn>(std::marker::PhantomData<fn(F)>);
+
+impl<F: MockFn> CustomDebug<F> {
+    /// Custom debug functions of the MockFn being matched.
+    pub fn of(_: &Matching<F>) -> Self {
+        Self(std::marker::PhantomData)
+    }
+
+    /// Wrap an expected value for rendering.
+    pub fn expected<T: ?Sized>(self, expected: &T) -> Expected<'_, F, T> {
+        Expected(expected, std::marker::PhantomData)
+    }
+}
+
+impl<F: MockFn> Clone for CustomDebug<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: MockFn> Copy for CustomDebug<F> {}
+
+/// An expected value, see [CustomDebug].
+pub struct Expected<'e, F, T: ?Sized>(&'e T, std::marker::PhantomData<fn(F)>);
+
+/// Trait for rendering an expected value with the custom debug function of its input.
+pub trait ProperCustomDebug {
+    /// Render the expected value of the input at the given index.
+    fn unimock_custom_debug(&self, index: usize) -> Option<String>;
+}
+
+/// Fallback trait (using autoref specialization) for expected values that have no custom debug function.
+pub trait NoCustomDebug {
+    /// Render the expected value of the input at the given index.
+    fn unimock_custom_debug(&self, index: usize) -> Option<String>;
+}
+
+impl<'e, F: DebugExpected<T>, T: ?Sized> ProperCustomDebug for Expected<'e, F, T> {
+    fn unimock_custom_debug(&self, index: usize) -> Option<String> {
+        F::debug_expected(index, self.0)
+    }
+}
+
+impl<'e, F, T: ?Sized> NoCustomDebug for &Expected<'e, F, T> {
+    fn unimock_custom_debug(&self, _index: usize) -> Option<String> {
+        None
+    }
+}
+
+/// A [MockFn] with at least one input.
+///
+/// Implemented by `#[unimock]`, and used by the [matching] macro for patterns like `matching!(42, ..)`.
+pub trait FirstInput: MockFn {
+    /// The type of the first input.
+    type First<'i>;
+
+    /// Borrow the first input, which is the whole of the inputs if there is only one.
+    fn first_input<'a, 'i>(inputs: &'a Self::Inputs<'i>) -> &'a Self::First<'i>;
+}
+
+/// A [MockFn] with exactly one callback input, i.e. an `impl Fn*` parameter.
+///
+/// Implemented by `#[unimock]` for such methods, and used by [invokes_arg](crate::build::DefineResponse::invokes_arg).
+pub trait CallbackArg: MockFn {
+    /// The type of the callback as passed to unimock, e.g. `Box<dyn Fn(Event) + Send + 'i>`.
+    type Arg<'i>;
+
+    /// Take the callback out of the inputs.
+    fn callback_arg(inputs: Self::Inputs<'_>) -> Self::Arg<'_>;
 }
 
 /// A reporter used in call pattern matchers in case of mismatched inputs.
@@ -96,6 +285,7 @@ where
 /// Used by the [matching] macro.
 pub struct MismatchReporter {
     enabled: bool,
+    pub(crate) input_names: Option<&'static [&'static str]>,
     pub(crate) mismatches: Vec<(InputIndex, Mismatch)>,
 }
 
@@ -103,6 +293,7 @@ impl MismatchReporter {
     pub(crate) fn new_enabled() -> Self {
         Self {
             enabled: true,
+            input_names: None,
             mismatches: vec![],
         }
     }
@@ -110,6 +301,7 @@ impl MismatchReporter {
     pub(crate) fn new_disabled() -> Self {
         Self {
             enabled: false,
+            input_names: None,
             mismatches: vec![],
         }
     }
@@ -119,6 +311,11 @@ impl MismatchReporter {
         self.enabled
     }
 
+    /// Refer to mismatched inputs by these parameter names, instead of the names declared by the [MockFn]
+    pub fn use_input_names(&mut self, input_names: &'static [&'static str]) {
+        self.input_names = Some(input_names);
+    }
+
     /// Register failure to match a pattern
     #[deprecated]
     pub fn pat_fail(
@@ -143,6 +340,7 @@ impl MismatchReporter {
                 kind: MismatchKind::Pattern,
                 actual: actual.map(|dbg| dbg.into()),
                 expected: expected.into(),
+                custom_expected: None,
             },
         ));
     }
@@ -171,6 +369,28 @@ impl MismatchReporter {
                 kind: MismatchKind::Eq,
                 actual: actual.map(|dbg| dbg.into()),
                 expected: expected.into(),
+                custom_expected: None,
+            },
+        ));
+    }
+
+    /// Register a custom debug representation of the expected value of the last registered `eq!` or `ne!` failure,
+    /// for inputs annotated with `#[unimock(debug_with=..)]`.
+    pub fn custom_expected_debug(&mut self, custom_expected: Option<String>) {
+        if let Some((_, mismatch)) = self.mismatches.last_mut() {
+            mismatch.custom_expected = custom_expected;
+        }
+    }
+
+    /// Register failure of a guard expression, after all the inputs matched their patterns
+    pub fn guard_fail(&mut self, guard: impl Into<String>) {
+        self.mismatches.push((
+            InputIndex(0),
+            Mismatch {
+                kind: MismatchKind::Guard,
+                actual: None,
+                expected: guard.into(),
+                custom_expected: None,
             },
         ));
     }
@@ -186,6 +406,42 @@ impl MismatchReporter {
         self.ne_fail_opt_debug(input_index, filter_questionmark(actual.into()), expected);
     }
 
+    /// Register failure to match a regex
+    pub fn regex_fail_opt_debug(
+        &mut self,
+        input_index: usize,
+        actual: Option<impl Into<String>>,
+        regex: impl Into<String>,
+    ) {
+        self.mismatches.push((
+            InputIndex(input_index),
+            Mismatch {
+                kind: MismatchKind::Regex,
+                actual: actual.map(|dbg| dbg.into()),
+                expected: regex.into(),
+                custom_expected: None,
+            },
+        ));
+    }
+
+    /// Register failure for a [Matcher](crate::matchers::Matcher) check
+    pub fn matcher_fail_opt_debug(
+        &mut self,
+        input_index: usize,
+        actual: Option<impl Into<String>>,
+        description: impl Into<String>,
+    ) {
+        self.mismatches.push((
+            InputIndex(input_index),
+            Mismatch {
+                kind: MismatchKind::Matcher,
+                actual: actual.map(|dbg| dbg.into()),
+                expected: description.into(),
+                custom_expected: None,
+            },
+        ));
+    }
+
     /// Register failure for an ne check
     pub fn ne_fail_opt_debug(
         &mut self,
@@ -199,6 +455,7 @@ impl MismatchReporter {
                 kind: MismatchKind::Ne,
                 actual: actual.map(|dbg| dbg.into()),
                 expected: expected.into(),
+                custom_expected: None,
             },
         ));
     }
@@ -210,7 +467,119 @@ pub fn eval<'u, 'i, F>(unimock: &'u Unimock, inputs: F::Inputs<'i>) -> Evaluatio
 where
     F: MockFn + 'static,
 {
-    unimock.handle_error(eval::eval(&unimock.shared_state, inputs))
+    eval_at(unimock, inputs, std::panic::Location::caller())
+}
+
+/// Evaluate a [MockFn] given some inputs and the location of the call, to produce its output.
+///
+/// Used by async methods, which capture the location of the call before their future is created.
+#[track_caller]
+pub fn eval_at<'u, 'i, F>(
+    unimock: &'u Unimock,
+    inputs: F::Inputs<'i>,
+    call_site: &'static std::panic::Location<'static>,
+) -> Evaluation<'u, 'i, F>
+where
+    F: MockFn + 'static,
+{
+    trace_enter::<F>(unimock, &inputs, call_site);
+    unimock.handle_error(eval::eval(unimock, inputs, call_site))
+}
+
+/// Evaluate a [MockFn] given some inputs, to produce an output borrowed from the inputs.
+#[track_caller]
+pub fn eval_borrowing<'u, 'i, F, T>(
+    unimock: &'u Unimock,
+    inputs: F::Inputs<'i>,
+) -> BorrowingEvaluation<'i, F, T>
+where
+    F: MockFn<Response = output::StaticRef<T>> + 'static,
+    for<'u2> F::Output<'u2>: Output<'u2, output::StaticRef<T>, Type = &'static T>,
+    T: ?Sized + 'static,
+{
+    eval_borrowing_at(unimock, inputs, std::panic::Location::caller())
+}
+
+/// Like [eval_borrowing], given the location of the call, see [eval_at].
+#[track_caller]
+pub fn eval_borrowing_at<'u, 'i, F, T>(
+    unimock: &'u Unimock,
+    inputs: F::Inputs<'i>,
+    call_site: &'static std::panic::Location<'static>,
+) -> BorrowingEvaluation<'i, F, T>
+where
+    F: MockFn<Response = output::StaticRef<T>> + 'static,
+    for<'u2> F::Output<'u2>: Output<'u2, output::StaticRef<T>, Type = &'static T>,
+    T: ?Sized + 'static,
+{
+    trace_enter::<F>(unimock, &inputs, call_site);
+    unimock.handle_error(eval::eval_borrowing(unimock, inputs, call_site))
+}
+
+fn trace_enter<F: MockFn>(
+    unimock: &Unimock,
+    inputs: &F::Inputs<'_>,
+    call_site: &'static std::panic::Location<'static>,
+) {
+    unimock.shared_state.trace_enter(
+        || format!("{}{}", F::NAME, F::debug_inputs(inputs)),
+        call_site,
+    );
+}
+
+/// Handle for registering the output of a call in the call tree, see [trace_start].
+///
+/// Dropping the handle, e.g. when the call panics, ends the call in the call tree context of the thread or task.
+pub struct Trace {
+    shared_state: Option<std::sync::Weak<state::SharedState>>,
+    // The position of the call among the current calls
+    position: usize,
+}
+
+impl Drop for Trace {
+    fn drop(&mut self) {
+        if self.shared_state.is_some() {
+            CurrentCalls::truncate(self.position);
+        }
+    }
+}
+
+/// Start tracing the output of a call, before it is evaluated by [eval] or [eval_borrowing].
+///
+/// The handle neither borrows nor keeps alive the instance, so the call may consume it.
+/// When call tree tracing is disabled, the handle is empty.
+pub fn trace_start(unimock: &Unimock) -> Trace {
+    if unimock.shared_state.is_tracing() {
+        Trace {
+            shared_state: Some(std::sync::Arc::downgrade(&unimock.shared_state)),
+            position: CurrentCalls::len(),
+        }
+    } else {
+        Trace {
+            shared_state: None,
+            position: 0,
+        }
+    }
+}
+
+/// Register the output of a call evaluated by [eval] or [eval_borrowing], mocked or unmocked, for call tree tracing.
+///
+/// The `debug` function computes the debug representation of the output, if available.
+pub fn trace_output<T>(trace: Trace, output: T, debug: impl FnOnce(&T) -> Option<String>) -> T {
+    if let Some(shared_state) = trace.shared_state.as_ref().and_then(|weak| weak.upgrade()) {
+        shared_state.trace_exit(trace.position, || {
+            debug(&output).unwrap_or_else(|| "?".to_string())
+        });
+    }
+    output
+}
+
+/// Evaluate the future of an async method in the call tree context of the calling thread or task.
+///
+/// Calls made by the future, e.g. by an unmocked implementation, are nested under the call that created it,
+/// no matter which thread polls it or which other futures are polled in between.
+pub fn trace_future<Fut: std::future::Future>(future: Fut) -> TraceFuture<Fut> {
+    TraceFuture::new(future)
 }
 
 /// Trait for computing the proper [std::fmt::Debug] representation of a value.
@@ -254,12 +623,75 @@ impl<T> NoDebug for &T {
     }
 }
 
+/// Evaluate a [Matcher](crate::matchers::Matcher) used in `is!(_)` within the [matching] macro.
+pub fn matcher_matches<T, M>(matcher: &M, value: &T) -> bool
+where
+    T: ?Sized,
+    M: crate::matchers::Matcher<T>,
+{
+    matcher.matches(value)
+}
+
+/// Describe a [Matcher](crate::matchers::Matcher) used in `is!(_)` within the [matching] macro.
+pub fn describe_matcher<T, M>(matcher: &M, _value: &T) -> String
+where
+    T: ?Sized,
+    M: crate::matchers::Matcher<T>,
+{
+    crate::matchers::Description(matcher, std::marker::PhantomData).to_string()
+}
+
+/// Compile a regex used in `regex!(_)` within the [matching] macro.
+///
+/// This happens once per call pattern.
+#[cfg(feature = "regex")]
+pub fn compile_regex(pattern: &str) -> ::regex::Regex {
+    match ::regex::Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => panic!("Invalid regex in matching!: {err}"),
+    }
+}
+
+/// Evaluate a regex used in `regex!(_)` within the [matching] macro.
+#[cfg(feature = "regex")]
+pub fn regex_matches<T>(regex: &::regex::Regex, value: &T) -> bool
+where
+    T: AsRef<str> + ?Sized,
+{
+    regex.is_match(value.as_ref())
+}
+
+/// Describe a regex used in `regex!(_)` within the [matching] macro.
+#[cfg(feature = "regex")]
+pub fn describe_regex(regex: &::regex::Regex) -> String {
+    format!("{:?}", regex.as_str())
+}
+
+/// The debug representation of inputs annotated with `#[unimock(redact)]`.
+pub const REDACTED: &str = "<redacted>";
+
 /// Take a vector of strings, comma separate and put within parentheses.
 pub fn format_inputs(inputs: &[String]) -> String {
     let joined = inputs.join(", ");
     format!("({joined})")
 }
 
+/// Take a vector of strings, prefix each with the corresponding input name, comma separate and put within parentheses.
+///
+/// Inputs without a corresponding name, or with a name generated by unimock for a destructured parameter (starting with `__`),
+/// are rendered positionally.
+pub fn format_named_inputs(names: &[&str], inputs: &[String]) -> String {
+    let named: Vec<String> = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| match names.get(index) {
+            Some(name) if !name.is_empty() => format!("{name}: {input}"),
+            _ => input.clone(),
+        })
+        .collect();
+    format_inputs(&named)
+}
+
 /// Convert any type implementing `AsRef<str>` to a `&str`.
 pub fn as_str_ref<T>(input: &T) -> &str
 where
@@ -0,0 +1,721 @@
+//! A matcher is used within `matching!` by enclosing it in `is!(_)`:
+//!
+//! ```rust
+//! # use unimock::*;
+//! use unimock::matchers::*;
+//!
+//! #[unimock(api=Mock)]
+//! trait Trait {
+//!     fn func(&self, name: String, ratio: f64, tags: Vec<String>) -> i32;
+//! }
+//!
+//! let u = Unimock::new(
+//!     Mock::func
+//!         .each_call(matching!(
+//!             is!(all_of((starts_with("user_"), not(contains("admin"))))),
+//!             is!(approx(0.5, 0.01)),
+//!             is!(any_of((len(0), each(len(3)))))
+//!         ))
+//!         .returns(42)
+//! );
+//!
+//! assert_eq!(42, u.func("user_bob".to_string(), 0.501, vec!["foo".to_string()]));
+//! ```
+//!
+//! Matchers are composable, and describe themselves in mismatch diagnostics.
+//! Custom matchers can be made by implementing [Matcher].
+//!
+//! All the inputs of a call can also be matched by matchers without using a macro, see [args].
+
+use crate::macro_api::{Matching, MismatchReporter};
+use crate::MockFn;
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A predicate over a value of type `T`, which is able to describe itself.
+pub trait Matcher<T: ?Sized> {
+    /// Whether the value matches.
+    fn matches(&self, value: &T) -> bool;
+
+    /// Describe what this matcher expects, for mismatch diagnostics.
+    ///
+    /// The description is conventionally written as the expression that constructed the matcher, e.g. `contains("foo")`.
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result;
+}
+
+impl<T: ?Sized, M: Matcher<T> + ?Sized> Matcher<T> for &M {
+    fn matches(&self, value: &T) -> bool {
+        (**self).matches(value)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (**self).describe(f)
+    }
+}
+
+impl<T: ?Sized, M: Matcher<T> + ?Sized> Matcher<T> for Box<M> {
+    fn matches(&self, value: &T) -> bool {
+        (**self).matches(value)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (**self).describe(f)
+    }
+}
+
+/// A tuple of matchers, used by [any_of] and [all_of].
+pub trait MatcherTuple<T: ?Sized> {
+    /// Whether any of the matchers match.
+    fn any_matches(&self, value: &T) -> bool;
+
+    /// Whether all of the matchers match.
+    fn all_match(&self, value: &T) -> bool;
+
+    /// Describe the matchers as a comma separated list.
+    fn describe_each(&self, f: &mut Formatter<'_>) -> std::fmt::Result;
+}
+
+macro_rules! matcher_tuple {
+    ($($m:ident: $i:tt),+) => {
+        impl<T: ?Sized, $($m: Matcher<T>),+> MatcherTuple<T> for ($($m,)+) {
+            fn any_matches(&self, value: &T) -> bool {
+                $(self.$i.matches(value))||+
+            }
+
+            fn all_match(&self, value: &T) -> bool {
+                $(self.$i.matches(value))&&+
+            }
+
+            fn describe_each(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                let mut separator = "";
+                $(
+                    f.write_str(separator)?;
+                    self.$i.describe(f)?;
+                    separator = ", ";
+                )+
+                let _ = separator;
+                Ok(())
+            }
+        }
+    };
+}
+
+matcher_tuple!(M0: 0);
+matcher_tuple!(M0: 0, M1: 1);
+matcher_tuple!(M0: 0, M1: 1, M2: 2);
+matcher_tuple!(M0: 0, M1: 1, M2: 2, M3: 3);
+matcher_tuple!(M0: 0, M1: 1, M2: 2, M3: 3, M4: 4);
+matcher_tuple!(M0: 0, M1: 1, M2: 2, M3: 3, M4: 4, M5: 5);
+
+/// A collection that is able to look for a needle, used by [contains].
+///
+/// For strings the needle is a substring, for sequences and sets it is an element, and for maps it is a key.
+pub trait Haystack<N: ?Sized> {
+    /// Whether the needle is contained.
+    fn contains_needle(&self, needle: &N) -> bool;
+}
+
+impl<N: AsRef<str> + ?Sized> Haystack<N> for str {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.contains(needle.as_ref())
+    }
+}
+
+impl<N: AsRef<str> + ?Sized> Haystack<N> for String {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.contains(needle.as_ref())
+    }
+}
+
+impl<E: PartialEq<N>, N> Haystack<N> for [E] {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<E: PartialEq<N>, N, const LEN: usize> Haystack<N> for [E; LEN] {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<E: PartialEq<N>, N> Haystack<N> for Vec<E> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<E: PartialEq<N>, N> Haystack<N> for VecDeque<E> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<E: PartialEq<N>, N, S> Haystack<N> for HashSet<E, S> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<E: PartialEq<N>, N> Haystack<N> for BTreeSet<E> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.iter().any(|elem| elem == needle)
+    }
+}
+
+impl<K: PartialEq<N>, V, N, S> Haystack<N> for HashMap<K, V, S> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.keys().any(|key| key == needle)
+    }
+}
+
+impl<K: PartialEq<N>, V, N> Haystack<N> for BTreeMap<K, V> {
+    fn contains_needle(&self, needle: &N) -> bool {
+        self.keys().any(|key| key == needle)
+    }
+}
+
+impl<H: Haystack<N> + ?Sized, N: ?Sized> Haystack<N> for &H {
+    fn contains_needle(&self, needle: &N) -> bool {
+        (**self).contains_needle(needle)
+    }
+}
+
+/// A value with a length, used by [len].
+pub trait Length {
+    /// The length of the value.
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<E> Length for [E] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<E, const LEN: usize> Length for [E; LEN] {
+    fn length(&self) -> usize {
+        LEN
+    }
+}
+
+impl<E> Length for Vec<E> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<E> Length for VecDeque<E> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<E, S> Length for HashSet<E, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<E> Length for BTreeSet<E> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> Length for HashMap<K, V, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<L: Length + ?Sized> Length for &L {
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+/// A collection of elements, used by [each].
+pub trait Elements {
+    /// The element type.
+    type Element;
+
+    /// Iterate the elements.
+    fn elements(&self) -> Box<dyn Iterator<Item = &Self::Element> + '_>;
+}
+
+impl<E> Elements for [E] {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<E, const LEN: usize> Elements for [E; LEN] {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<E> Elements for Vec<E> {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<E> Elements for VecDeque<E> {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<E, S> Elements for HashSet<E, S> {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<E> Elements for BTreeSet<E> {
+    type Element = E;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &E> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<C: Elements + ?Sized> Elements for &C {
+    type Element = C::Element;
+
+    fn elements(&self) -> Box<dyn Iterator<Item = &C::Element> + '_> {
+        (**self).elements()
+    }
+}
+
+/// Matches strings containing a substring, sequences and sets containing an element, or maps containing a key.
+pub fn contains<N: Debug>(needle: N) -> Contains<N> {
+    Contains(needle)
+}
+
+/// Matcher returned by [contains].
+pub struct Contains<N>(N);
+
+impl<T: Haystack<N> + ?Sized, N: Debug> Matcher<T> for Contains<N> {
+    fn matches(&self, value: &T) -> bool {
+        value.contains_needle(&self.0)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "contains({:?})", self.0)
+    }
+}
+
+/// Matches strings starting with the given prefix.
+pub fn starts_with<P: AsRef<str>>(prefix: P) -> StartsWith<P> {
+    StartsWith(prefix)
+}
+
+/// Matcher returned by [starts_with].
+pub struct StartsWith<P>(P);
+
+impl<T: AsRef<str> + ?Sized, P: AsRef<str>> Matcher<T> for StartsWith<P> {
+    fn matches(&self, value: &T) -> bool {
+        value.as_ref().starts_with(self.0.as_ref())
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "starts_with({:?})", self.0.as_ref())
+    }
+}
+
+/// Matches floating point numbers within `epsilon` of `expected`.
+pub fn approx<F>(expected: F, epsilon: F) -> Approx<F> {
+    Approx { expected, epsilon }
+}
+
+/// Matcher returned by [approx].
+pub struct Approx<F> {
+    expected: F,
+    epsilon: F,
+}
+
+macro_rules! approx_matcher {
+    ($float:ty) => {
+        impl Matcher<$float> for Approx<$float> {
+            fn matches(&self, value: &$float) -> bool {
+                (*value - self.expected).abs() <= self.epsilon
+            }
+
+            fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "approx({:?}, {:?})", self.expected, self.epsilon)
+            }
+        }
+    };
+}
+
+approx_matcher!(f32);
+approx_matcher!(f64);
+
+/// Matches strings, sequences, sets and maps of the given length.
+pub fn len(len: usize) -> Len {
+    Len(len)
+}
+
+/// Matcher returned by [len].
+pub struct Len(usize);
+
+impl<T: Length + ?Sized> Matcher<T> for Len {
+    fn matches(&self, value: &T) -> bool {
+        value.length() == self.0
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "len({})", self.0)
+    }
+}
+
+/// Matches if any of the matchers in the tuple match.
+pub fn any_of<M>(matchers: M) -> AnyOf<M> {
+    AnyOf(matchers)
+}
+
+/// Matcher returned by [any_of].
+pub struct AnyOf<M>(M);
+
+impl<T: ?Sized, M: MatcherTuple<T>> Matcher<T> for AnyOf<M> {
+    fn matches(&self, value: &T) -> bool {
+        self.0.any_matches(value)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("any_of(")?;
+        self.0.describe_each(f)?;
+        f.write_str(")")
+    }
+}
+
+/// Matches if all of the matchers in the tuple match.
+pub fn all_of<M>(matchers: M) -> AllOf<M> {
+    AllOf(matchers)
+}
+
+/// Matcher returned by [all_of].
+pub struct AllOf<M>(M);
+
+impl<T: ?Sized, M: MatcherTuple<T>> Matcher<T> for AllOf<M> {
+    fn matches(&self, value: &T) -> bool {
+        self.0.all_match(value)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("all_of(")?;
+        self.0.describe_each(f)?;
+        f.write_str(")")
+    }
+}
+
+/// Matches if the given matcher does not match.
+pub fn not<M>(matcher: M) -> Not<M> {
+    Not(matcher)
+}
+
+/// Matcher returned by [not].
+pub struct Not<M>(M);
+
+impl<T: ?Sized, M: Matcher<T>> Matcher<T> for Not<M> {
+    fn matches(&self, value: &T) -> bool {
+        !self.0.matches(value)
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not(")?;
+        self.0.describe(f)?;
+        f.write_str(")")
+    }
+}
+
+/// Matches sequences and sets where every element matches the given matcher.
+pub fn each<M>(matcher: M) -> Each<M> {
+    Each(matcher)
+}
+
+/// Matcher returned by [each].
+pub struct Each<M>(M);
+
+impl<T: Elements + ?Sized, M: Matcher<T::Element>> Matcher<T> for Each<M> {
+    fn matches(&self, value: &T) -> bool {
+        value.elements().all(|element| self.0.matches(element))
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("each(")?;
+        self.0.describe(f)?;
+        f.write_str(")")
+    }
+}
+
+/// Match the inputs of a call with a tuple of [Matcher]s, one for each input, without using the [matching](crate::matching) macro.
+///
+/// Mismatches are reported in the same way as for `is!(_)` in the [matching](crate::matching) macro.
+/// Like the closure produced by `matching!`, the returned input matcher is passed to call pattern builders by reference.
+///
+/// ```rust
+/// # use unimock::*;
+/// use unimock::matchers::*;
+///
+/// #[unimock(api=Mock)]
+/// trait Trait {
+///     fn func(&self, a: i32, b: String, c: &str) -> i32;
+/// }
+///
+/// let u = Unimock::new(
+///     Mock::func
+///         .each_call(&args((eq(1), any(), pred(|s: &str| s.len() > 3))))
+///         .returns(42)
+/// );
+///
+/// assert_eq!(42, u.func(1, "b".to_string(), "long"));
+/// ```
+///
+/// Methods with a single input take a one-element tuple: `args((eq(1),))`.
+pub fn args<F, M>(matchers: M) -> impl Fn(&mut Matching<F>)
+where
+    F: MatchArgs<M>,
+    M: Send + Sync + 'static,
+{
+    let matchers = Arc::new(matchers);
+    move |matching| {
+        let matchers = matchers.clone();
+        let pat_debug = F::describe_args(&matchers);
+        matching.debug_func(move |inputs, reporter| F::matches_args(&matchers, inputs, reporter));
+        matching.pat_debug_at_location(pat_debug);
+    }
+}
+
+/// Implemented for every [MockFn] whose inputs can be matched by the tuple of matchers `M`, used by [args].
+pub trait MatchArgs<M>: MockFn {
+    /// Whether the inputs match, reporting every mismatched input.
+    fn matches_args(
+        matchers: &M,
+        inputs: &Self::Inputs<'_>,
+        reporter: &mut MismatchReporter,
+    ) -> bool;
+
+    /// Describe the matchers as a parenthesized list.
+    fn describe_args(matchers: &M) -> String;
+}
+
+impl<F, M> MatchArgs<M> for F
+where
+    F: MockFn,
+    M: for<'i> ArgsMatcher<F::Inputs<'i>>,
+{
+    fn matches_args(
+        matchers: &M,
+        inputs: &Self::Inputs<'_>,
+        reporter: &mut MismatchReporter,
+    ) -> bool {
+        matchers.matches_args(inputs, &|index| F::debug_input(inputs, index), reporter)
+    }
+
+    fn describe_args(matchers: &M) -> String {
+        matchers.describe_args()
+    }
+}
+
+/// A tuple of [Matcher]s for all the inputs of a function, used by [args].
+pub trait ArgsMatcher<I> {
+    /// Whether the inputs match, reporting every mismatched input.
+    ///
+    /// `debug_input` renders the actual input at the given index, for the mismatch report.
+    fn matches_args(
+        &self,
+        inputs: &I,
+        debug_input: &dyn Fn(usize) -> Option<String>,
+        reporter: &mut MismatchReporter,
+    ) -> bool;
+
+    /// Describe the matchers as a parenthesized list.
+    fn describe_args(&self) -> String;
+}
+
+fn report_arg<T: ?Sized, M: Matcher<T>>(
+    matcher: &M,
+    index: usize,
+    input: &T,
+    debug_input: &dyn Fn(usize) -> Option<String>,
+    reporter: &mut MismatchReporter,
+) -> bool {
+    if matcher.matches(input) {
+        true
+    } else {
+        if reporter.enabled() {
+            reporter.matcher_fail_opt_debug(
+                index,
+                debug_input(index),
+                Description(matcher, PhantomData).to_string(),
+            );
+        }
+        false
+    }
+}
+
+impl ArgsMatcher<()> for () {
+    fn matches_args(
+        &self,
+        _: &(),
+        _: &dyn Fn(usize) -> Option<String>,
+        _: &mut MismatchReporter,
+    ) -> bool {
+        true
+    }
+
+    fn describe_args(&self) -> String {
+        "()".to_string()
+    }
+}
+
+impl<A, M0: Matcher<A>> ArgsMatcher<A> for (M0,) {
+    fn matches_args(
+        &self,
+        input: &A,
+        debug_input: &dyn Fn(usize) -> Option<String>,
+        reporter: &mut MismatchReporter,
+    ) -> bool {
+        report_arg(&self.0, 0, input, debug_input, reporter)
+    }
+
+    fn describe_args(&self) -> String {
+        format!("({})", Description(&self.0, PhantomData::<fn(&A)>))
+    }
+}
+
+macro_rules! args_matcher {
+    ($($a:ident: $m:ident: $i:tt),+) => {
+        impl<$($a, $m: Matcher<$a>),+> ArgsMatcher<($($a,)+)> for ($($m,)+) {
+            fn matches_args(
+                &self,
+                inputs: &($($a,)+),
+                debug_input: &dyn Fn(usize) -> Option<String>,
+                reporter: &mut MismatchReporter,
+            ) -> bool {
+                let mut matched = true;
+                $(
+                    if !report_arg(&self.$i, $i, &inputs.$i, debug_input, reporter) {
+                        if !reporter.enabled() {
+                            return false;
+                        }
+                        matched = false;
+                    }
+                )+
+                matched
+            }
+
+            fn describe_args(&self) -> String {
+                let descriptions = [$(Description(&self.$i, PhantomData::<fn(&$a)>).to_string()),+];
+                format!("({})", descriptions.join(", "))
+            }
+        }
+    };
+}
+
+args_matcher!(A0: M0: 0, A1: M1: 1);
+args_matcher!(A0: M0: 0, A1: M1: 1, A2: M2: 2);
+args_matcher!(A0: M0: 0, A1: M1: 1, A2: M2: 2, A3: M3: 3);
+args_matcher!(A0: M0: 0, A1: M1: 1, A2: M2: 2, A3: M3: 3, A4: M4: 4);
+args_matcher!(A0: M0: 0, A1: M1: 1, A2: M2: 2, A3: M3: 3, A4: M4: 4, A5: M5: 5);
+
+/// Matches values equal to the given value.
+pub fn eq<E: Debug>(expected: E) -> Equals<E> {
+    Equals(expected)
+}
+
+/// Matcher returned by [eq].
+pub struct Equals<E>(E);
+
+impl<T: PartialEq<E> + ?Sized, E: Debug> Matcher<T> for Equals<E> {
+    fn matches(&self, value: &T) -> bool {
+        value == &self.0
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "eq({:?})", self.0)
+    }
+}
+
+/// Matches any value.
+pub fn any() -> Anything {
+    Anything
+}
+
+/// Matcher returned by [any].
+pub struct Anything;
+
+impl<T: ?Sized> Matcher<T> for Anything {
+    fn matches(&self, _: &T) -> bool {
+        true
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("any()")
+    }
+}
+
+/// Matches values for which the predicate returns `true`.
+///
+/// The predicate may take a borrowed form of the value, e.g. `&str` for `String` or `&'a str` inputs.
+pub fn pred<U: ?Sized, P: Fn(&U) -> bool>(predicate: P) -> Pred<P, U> {
+    Pred(predicate, PhantomData)
+}
+
+/// Matcher returned by [pred].
+pub struct Pred<P, U: ?Sized>(P, PhantomData<fn(&U)>);
+
+impl<T: Borrow<U> + ?Sized, U: ?Sized, P: Fn(&U) -> bool> Matcher<T> for Pred<P, U> {
+    fn matches(&self, value: &T) -> bool {
+        (self.0)(value.borrow())
+    }
+
+    fn describe(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("pred(..)")
+    }
+}
+
+/// Displays the description of a [Matcher].
+pub(crate) struct Description<'m, T: ?Sized, M>(pub &'m M, pub PhantomData<fn(&T)>);
+
+impl<'m, T: ?Sized, M: Matcher<T>> std::fmt::Display for Description<'m, T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.describe(f)
+    }
+}
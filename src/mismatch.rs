@@ -2,28 +2,245 @@ use std::{collections::HashSet, fmt::Display};
 
 use crate::{
     call_pattern::{InputIndex, PatIndex},
-    macro_api::MismatchReporter,
+    debug::{truncate, truncate_diff},
+    macro_api::{MismatchReporter, REDACTED},
+    structural_diff,
 };
 
+/// How mismatched values are compared in mismatch reports.
+///
+/// Configured per [Unimock](crate::Unimock) instance using [with_diff_mode](crate::Unimock::with_diff_mode).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DiffMode {
+    /// Compare the complete `Debug` representations of the actual and expected values.
+    #[default]
+    Text,
+    /// Only report the paths of differing fields in the `Debug` representations, e.g. `items[2].price: 10 != 12`.
+    ///
+    /// Falls back to [DiffMode::Text] for representations that can not be parsed or are nested too deeply,
+    /// which includes representations truncated by [with_max_input_len](crate::Unimock::with_max_input_len).
+    /// Sets, e.g. of a `HashSet`, are compared without regard to the order of their elements.
+    Structural,
+}
+
+/// How much detail is included in mismatch reports.
+///
+/// Configured per [Unimock](crate::Unimock) instance using [with_verbosity](crate::Unimock::with_verbosity).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Verbosity {
+    /// Only report the mismatches of the call pattern closest to matching the call, and of call patterns as close as that one.
+    #[default]
+    Concise,
+    /// Report the mismatches of every call pattern.
+    Verbose,
+}
+
+/// Limits on the size of values rendered in error messages.
+///
+/// Configured per [Unimock](crate::Unimock) instance using
+/// [with_max_input_len](crate::Unimock::with_max_input_len) and [with_max_diff_len](crate::Unimock::with_max_diff_len).
+#[derive(Clone, Copy)]
+pub(crate) struct OutputLimits {
+    /// Maximum length in bytes of rendered inputs, and of values passed to the diff.
+    pub max_input_len: usize,
+    /// Maximum length in bytes of a rendered diff.
+    pub max_diff_len: usize,
+}
+
+impl Default for OutputLimits {
+    fn default() -> Self {
+        Self {
+            max_input_len: 4 * 1024,
+            max_diff_len: 16 * 1024,
+        }
+    }
+}
+
+/// The call pattern whose inputs came closest to matching a call.
+#[derive(Clone, Copy)]
+pub(crate) struct ClosestPattern {
+    pub pat_index: PatIndex,
+    pub differing_inputs: usize,
+    pub guard_failed: bool,
+}
+
 #[derive(Clone)]
 pub(crate) struct Mismatches {
-    mismatches: Vec<(PatIndex, InputIndex, Mismatch)>,
+    mismatches: Vec<(PatIndex, InputIndex, Option<&'static str>, Mismatch)>,
+    // Source locations of the call patterns that reported mismatches, where known
+    pattern_locations: Vec<(PatIndex, (&'static str, u32))>,
+    diff_mode: DiffMode,
+    output_limits: OutputLimits,
 }
 
 impl Mismatches {
-    pub fn new() -> Self {
-        Self { mismatches: vec![] }
+    pub fn new(diff_mode: DiffMode, output_limits: OutputLimits) -> Self {
+        Self {
+            mismatches: vec![],
+            pattern_locations: vec![],
+            diff_mode,
+            output_limits,
+        }
     }
 
-    pub fn collect_from_reporter(&mut self, pat_index: PatIndex, reporter: MismatchReporter) {
+    pub fn collect_from_reporter(
+        &mut self,
+        pat_index: PatIndex,
+        pattern_location: Option<(&'static str, u32)>,
+        input_names: &'static [&'static str],
+        reporter: MismatchReporter,
+    ) {
+        if let Some(pattern_location) = pattern_location {
+            self.pattern_locations.push((pat_index, pattern_location));
+        }
         for (input_index, mismatch) in reporter.mismatches {
-            self.mismatches.push((pat_index, input_index, mismatch));
+            let input_name = reporter
+                .input_names
+                .unwrap_or(input_names)
+                .get(input_index.0)
+                .copied()
+                .filter(|name| !name.is_empty());
+            self.mismatches
+                .push((pat_index, input_index, input_name, mismatch));
+        }
+    }
+
+    /// Rank the call patterns by the number of differing inputs, and pick the one with the fewest.
+    /// Ties go to the earliest call pattern.
+    ///
+    /// Call patterns that did not report any mismatches can't be ranked.
+    pub fn closest_pattern(&self) -> Option<ClosestPattern> {
+        self.ranked_patterns()
+            .min_by_key(|closest| (closest.differing_inputs, closest.pat_index.0))
+    }
+
+    fn ranked_patterns(&self) -> impl Iterator<Item = ClosestPattern> + '_ {
+        self.pattern_indexes().into_iter().map(|pat_index| {
+            let mut differing: Vec<usize> = vec![];
+            let mut guard_failed = false;
+            for (_, input_index, _, mismatch) in
+                self.mismatches.iter().filter(|(p, ..)| p.0 == pat_index)
+            {
+                match mismatch.kind {
+                    MismatchKind::Guard => guard_failed = true,
+                    _ => {
+                        if !differing.contains(&input_index.0) {
+                            differing.push(input_index.0);
+                        }
+                    }
+                }
+            }
+            ClosestPattern {
+                pat_index: PatIndex(pat_index),
+                differing_inputs: differing.len(),
+                guard_failed,
+            }
+        })
+    }
+
+    /// The indexes of the call patterns that reported mismatches.
+    fn pattern_indexes(&self) -> Vec<usize> {
+        let mut pat_indexes: Vec<usize> = self.mismatches.iter().map(|(p, ..)| p.0).collect();
+        pat_indexes.dedup();
+        pat_indexes
+    }
+
+    /// The source location of the given call pattern, if known.
+    pub fn pattern_location(&self, pat_index: PatIndex) -> Option<(&'static str, u32)> {
+        self.pattern_locations
+            .iter()
+            .find(|(index, _)| index.0 == pat_index.0)
+            .map(|(_, location)| *location)
+    }
+
+    /// Truncate a value of a mismatch to the output limits.
+    pub fn truncate_value<'s>(&self, value: &'s str) -> std::borrow::Cow<'s, str> {
+        truncate(value, self.output_limits.max_input_len)
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = &(PatIndex, InputIndex, Option<&'static str>, Mismatch)> {
+        self.mismatches.iter()
+    }
+
+    /// Replace the actual values of inputs that have a custom debug representation.
+    ///
+    /// The expected values are replaced too, as they likely contain what the custom representation hides.
+    /// They are only shown if they could be rendered by the same custom debug function, which redacted inputs don't have.
+    pub fn apply_custom_debug(&mut self, custom_input_debugger: &dyn Fn(usize) -> Option<String>) {
+        for (_, input_index, _, mismatch) in &mut self.mismatches {
+            if let MismatchKind::Guard = mismatch.kind {
+                continue;
+            }
+            if let Some(custom) = custom_input_debugger(input_index.0) {
+                mismatch.expected = match mismatch.custom_expected.take() {
+                    Some(custom_expected) if custom != REDACTED => custom_expected,
+                    _ => REDACTED.to_string(),
+                };
+                mismatch.actual = Some(custom);
+            }
+        }
+    }
+
+    /// Only keep the mismatches of the closest call pattern, and of the call patterns ranked equal to it.
+    ///
+    /// Returns the number of call patterns whose mismatches were dropped.
+    pub fn retain_closest(&mut self, closest: &ClosestPattern) -> usize {
+        let retained: Vec<usize> = self
+            .ranked_patterns()
+            .filter(|ranked| ranked.differing_inputs == closest.differing_inputs)
+            .map(|ranked| ranked.pat_index.0)
+            .collect();
+        let reported_count = self.pattern_indexes().len();
+
+        self.mismatches
+            .retain(|(index, _, _, _)| retained.contains(&index.0));
+
+        reported_count - retained.len()
+    }
+
+    /// The values are truncated before they get parsed, so truncated values fall back to a text diff.
+    fn structural_diff(&self, actual: &str, expected: &str) -> Option<Vec<String>> {
+        match self.diff_mode {
+            DiffMode::Text => None,
+            DiffMode::Structural => {
+                let max_input_len = self.output_limits.max_input_len;
+                structural_diff::structural_diff(
+                    &truncate(actual, max_input_len),
+                    &truncate(expected, max_input_len),
+                )
+                .filter(|lines| !lines.is_empty())
+            }
+        }
+    }
+
+    /// Write a diff of the actual and expected values, within the output limits.
+    ///
+    /// The values are truncated before diffing, which keeps the diff computation bounded.
+    fn fmt_diff(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        actual: &str,
+        expected: &str,
+    ) -> std::fmt::Result {
+        let OutputLimits {
+            max_input_len,
+            max_diff_len,
+        } = self.output_limits;
+        let actual = truncate(actual, max_input_len);
+        let expected = truncate(expected, max_input_len);
+        let diff = Diff::new(&actual, &expected).to_string();
+
+        match truncate_diff(&diff, max_diff_len) {
+            std::borrow::Cow::Borrowed(diff) => write!(f, "{diff}"),
+            std::borrow::Cow::Owned(diff) => writeln!(f, "{diff}"),
         }
     }
 
     fn has_unique_pat_index(&self) -> bool {
         let mut pat_indexes = HashSet::new();
-        for (pat_index, _, _) in &self.mismatches {
+        for (pat_index, _, _, _) in &self.mismatches {
             pat_indexes.insert(pat_index.0);
         }
 
@@ -39,45 +256,78 @@ impl Display for Mismatches {
 
         let is_unique_pat = self.has_unique_pat_index();
 
-        for (pat_index, input_index, mismatch) in &self.mismatches {
+        for (pat_index, input_index, input_name, mismatch) in &self.mismatches {
             let Mismatch {
                 kind,
                 actual,
                 expected,
+                ..
             } = mismatch;
             let mut header_msg = MismatchMsg::new(*pat_index, *input_index, is_unique_pat, *kind);
+            header_msg.input_name = *input_name;
+
+            if actual.as_deref() == Some(REDACTED) {
+                header_msg.fmt(f)?;
+                writeln!(
+                    f,
+                    "The input is redacted, so its actual and expected values are not shown."
+                )?;
+                continue;
+            }
+
+            if let (Some(actual), REDACTED) = (actual, expected.as_str()) {
+                header_msg.fmt(f)?;
+                writeln!(f, "The input has a custom debug representation, so its expected value is not shown. Actual value:")?;
+                writeln!(f, "{}", truncate(actual, self.output_limits.max_input_len))?;
+                continue;
+            }
 
             match (kind, actual) {
-                (MismatchKind::Pattern, Some(actual)) => {
+                (MismatchKind::Pattern | MismatchKind::Regex, Some(actual)) => {
                     header_msg.has_comparison = true;
                     header_msg.fmt(f)?;
-                    Diff::new(actual, expected).fmt(f)?;
+                    self.fmt_diff(f, actual, expected)?;
                 }
                 (MismatchKind::Eq, Some(actual)) => {
                     if actual == expected {
                         header_msg.fmt(f)?;
 
                         write!(f, "Actual value did not equal expected value, but their Debug representation are identical:")?;
-                        write!(f, "{actual}")?;
+                        write!(f, "{}", truncate(actual, self.output_limits.max_input_len))?;
                     } else {
                         header_msg.has_comparison = true;
                         header_msg.fmt(f)?;
 
-                        Diff::new(actual, expected).fmt(f)?;
+                        match self.structural_diff(actual, expected) {
+                            Some(lines) => {
+                                let lines = lines.join("\n");
+                                writeln!(
+                                    f,
+                                    "{}",
+                                    truncate(&lines, self.output_limits.max_diff_len)
+                                )?;
+                            }
+                            None => self.fmt_diff(f, actual, expected)?,
+                        }
                     }
                 }
                 (MismatchKind::Ne, Some(actual)) => {
                     if actual == expected {
                         header_msg.fmt(f)?;
-                        write!(f, "{actual}")?;
+                        write!(f, "{}", truncate(actual, self.output_limits.max_input_len))?;
                     } else {
                         header_msg.has_comparison = true;
                         header_msg.fmt(f)?;
 
                         writeln!(f, "(Warning) Debug representation problem: Expected and actual asserted inequality failed, though Debug representations differ:")?;
-                        Diff::new(actual, expected).fmt(f)?;
+                        self.fmt_diff(f, actual, expected)?;
                     }
                 }
+                (MismatchKind::Matcher, Some(actual)) => {
+                    header_msg.fmt(f)?;
+                    writeln!(f, "Actual value did not satisfy `{expected}`:")?;
+                    writeln!(f, "{}", truncate(actual, self.output_limits.max_input_len))?;
+                }
                 (MismatchKind::Pattern, None) => {
                     header_msg.fmt(f)?;
                     writeln!(f, "Actual value did not match expected pattern, but can't display diagnostics because the type is likely missing #[derive(Debug)].")?;
@@ -86,6 +336,18 @@ impl Display for Mismatches {
                     header_msg.fmt(f)?;
                     writeln!(f, "Actual value did not equal expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)].")?;
                 }
+                (MismatchKind::Regex, None) => {
+                    header_msg.fmt(f)?;
+                    writeln!(f, "Actual value did not match expected regex, but can't display diagnostics because the type is likely missing #[derive(Debug)].")?;
+                }
+                (MismatchKind::Matcher, None) => {
+                    header_msg.fmt(f)?;
+                    writeln!(f, "Actual value did not satisfy `{expected}`, but can't display diagnostics because the type is likely missing #[derive(Debug)].")?;
+                }
+                (MismatchKind::Guard, _) => {
+                    header_msg.fmt(f)?;
+                    writeln!(f, "All inputs matched the pattern, but the guard `{expected}` evaluated to false.")?;
+                }
                 (MismatchKind::Ne, None) => {
                     header_msg.fmt(f)?;
                     writeln!(f, "Actual value unexpectedly equalled expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)].")?;
@@ -102,6 +364,8 @@ pub(crate) struct Mismatch {
     pub kind: MismatchKind,
     pub actual: Option<String>,
     pub expected: String,
+    // The expected value rendered by the custom debug function of the input, if it has one
+    pub custom_expected: Option<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -109,11 +373,15 @@ pub(crate) enum MismatchKind {
     Pattern,
     Eq,
     Ne,
+    Regex,
+    Matcher,
+    Guard,
 }
 
 struct MismatchMsg {
     pat_index: PatIndex,
     input_index: InputIndex,
+    input_name: Option<&'static str>,
     is_unique_pat: bool,
     mismatch_kind: MismatchKind,
     has_comparison: bool,
@@ -129,6 +397,7 @@ impl MismatchMsg {
         Self {
             pat_index,
             input_index,
+            input_name: None,
             is_unique_pat,
             mismatch_kind,
             has_comparison: false,
@@ -142,21 +411,30 @@ impl Display for MismatchMsg {
             MismatchKind::Pattern => "Pattern mismatch for ",
             MismatchKind::Eq => "Equality mismatch for ",
             MismatchKind::Ne => "Inequality mismatch for ",
+            MismatchKind::Regex => "Regex mismatch for ",
+            MismatchKind::Matcher => "Matcher mismatch for ",
+            MismatchKind::Guard => "Guard mismatch",
         };
 
         write!(f, "{initial_msg}")?;
 
-        if self.is_unique_pat {
-            write!(f, "input #{}", self.input_index.0)?;
-        } else {
-            write!(
-                f,
-                "call pattern #{}, input #{}",
-                self.pat_index.0, self.input_index.0
-            )?;
+        if let MismatchKind::Guard = self.mismatch_kind {
+            if !self.is_unique_pat {
+                write!(f, " for call pattern #{}", self.pat_index.0)?;
+            }
+            return writeln!(f, ":");
+        }
+
+        if !self.is_unique_pat {
+            write!(f, "call pattern #{}, ", self.pat_index.0)?;
+        }
+
+        match self.input_name {
+            Some(input_name) => write!(f, "input `{input_name}`")?,
+            None => write!(f, "input #{}", self.input_index.0)?,
         }
 
-        if let MismatchKind::Pattern | MismatchKind::Eq = self.mismatch_kind {
+        if let MismatchKind::Pattern | MismatchKind::Eq | MismatchKind::Regex = self.mismatch_kind {
             if self.has_comparison {
                 write!(f, " (actual / expected)")?;
             }
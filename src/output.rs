@@ -1,7 +1,69 @@
 use crate::{call_pattern::DynResponder, value_chain::ValueChain, MockFn, Responder};
 use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
 /// Trait for responding to function calls.
+///
+/// Implementors of this trait are _shapes_: Type-level descriptions of how a response is stored inside Unimock
+/// before being converted into the output of a mocked function (see [Output]).
+///
+/// When a method returns an unknown generic type containing borrowed data, e.g. `Labeled<&str>`,
+/// unimock converts each of its type arguments into a shape, and uses `Labeled<Borrowed<str>>` as the shape of the output.
+/// Supporting such a type is a matter of implementing [Respond], [IntoResponseOnce] and [Output] for it:
+///
+/// ```rust
+/// use unimock::*;
+/// use unimock::output::*;
+/// use unimock::value_chain::ValueChain;
+///
+/// pub struct Labeled<T>(pub T);
+///
+/// impl<R: Respond> Respond for Labeled<R> {
+///     type Type = Labeled<R::Type>;
+/// }
+///
+/// impl<R: Respond, A: IntoResponseOnce<R>> IntoResponseOnce<Labeled<R>> for Labeled<A>
+/// where
+///     R::Type: Send + Sync,
+/// {
+///     fn into_response(self) -> Labeled<R::Type> {
+///         Labeled(self.0.into_response())
+///     }
+///
+///     fn into_once_responder<F: MockFn<Response = Labeled<R>>>(self) -> Responder {
+///         Responder::once::<F>(<Self as IntoResponseOnce<Labeled<R>>>::into_response(self))
+///     }
+/// }
+///
+/// impl<'u, R: Respond, O: Output<'u, R>> Output<'u, Labeled<R>> for Labeled<O> {
+///     type Type = Labeled<O::Type>;
+///
+///     fn from_response(response: Labeled<R::Type>, value_chain: &'u ValueChain) -> Self::Type {
+///         Labeled(O::from_response(response.0, value_chain))
+///     }
+///
+///     fn try_from_borrowed_response(
+///         response: &'u Labeled<R::Type>,
+///     ) -> Result<Self::Type, SignatureError> {
+///         Ok(Labeled(O::try_from_borrowed_response(&response.0)?))
+///     }
+/// }
+///
+/// #[unimock(api=LabelMock)]
+/// trait Label {
+///     fn label(&self) -> Labeled<&str>;
+/// }
+///
+/// let u = Unimock::new(
+///     LabelMock::label
+///         .next_call(matching!())
+///         .returns(Labeled(String::from("label"))),
+/// );
+/// assert_eq!("label", u.label().0);
+/// ```
 pub trait Respond {
     /// The type of the response, as stored temporarily inside Unimock.
     type Type: 'static;
@@ -11,18 +73,16 @@ pub trait Respond {
 ///
 /// This can be implemented by types that do not implement `Clone`.
 pub trait IntoResponseOnce<R: Respond> {
-    // Convert this type into the output type.
-    #[doc(hidden)]
+    /// Convert this type into the response type.
     fn into_response(self) -> <R as Respond>::Type;
 
-    // Convert this type directly into a responder that can respond (at least) once.
-    #[doc(hidden)]
+    /// Convert this type directly into a responder that can respond (at least) once.
     fn into_once_responder<F: MockFn<Response = R>>(self) -> Responder;
 }
 
 /// Trait for `Clone` values which can be converted into a reusable multi-value responder.
 pub trait IntoResponseClone<R: Respond>: IntoResponseOnce<R> {
-    #[doc(hidden)]
+    /// Convert this type into a responder that can respond any number of times.
     fn into_clone_responder<F: MockFn<Response = R>>(self) -> Responder;
 }
 
@@ -34,35 +94,149 @@ pub trait Output<'u, R: Respond> {
     /// The type of the output compatible with the function signature.
     type Type;
 
-    #[doc(hidden)]
+    /// Convert an owned response into the output.
+    ///
+    /// Parts of the response that need to be borrowed by the output can be stored in the [ValueChain].
     fn from_response(response: R::Type, value_chain: &'u ValueChain) -> Self::Type;
 
-    #[doc(hidden)]
+    /// Convert a response borrowed from Unimock into the output, which is used by responders that respond more than once without cloning.
+    ///
+    /// This fails with [SignatureError::OwnershipRequired] when the output needs to own some part of the response.
     fn try_from_borrowed_response(response: &'u R::Type) -> Result<Self::Type, SignatureError>;
 }
 
+/// An error produced when a response cannot be converted into an output.
 #[derive(Debug)]
-#[doc(hidden)]
 pub enum SignatureError {
+    /// The output requires ownership of the response.
     OwnershipRequired,
 }
 
-#[doc(hidden)]
+/// Shape of an output that is fully owned, e.g. `String`.
 pub struct Owned<T>(std::marker::PhantomData<T>);
 
-// This type describes a function response that is a reference borrowed from `Self`.
-#[doc(hidden)]
+/// Shape of an output that is a reference borrowed from `Self`, e.g. `&str`.
 pub struct Borrowed<T: ?Sized + 'static>(std::marker::PhantomData<T>);
 
-#[doc(hidden)]
+/// Shape of an output that is a static reference, e.g. `&'static str`.
 pub struct StaticRef<T: ?Sized>(std::marker::PhantomData<T>);
 
-// This type describes a function response that is a mix of owned and borrowed data.
-//
-// The typical example is `Option<&T>`.
-#[doc(hidden)]
+/// Marker for a [MockFn](crate::MockFn) whose output is a reference with the same lifetime as all of its non-self inputs,
+/// e.g. `fn first_token<'a>(&self, s: &'a str) -> &'a str`.
+///
+/// Implemented by `#[unimock]` for such methods, and required by [answers_borrowing](crate::build::DefineResponse::answers_borrowing).
+pub trait BorrowsFromInputs: crate::MockFn {}
+
+/// Shape of an output that is a mix of owned and borrowed data.
+///
+/// The typical example is `Option<&T>`.
+/// `Option`, `Vec`, `Result`, `HashMap`, `BTreeMap` and tuples can be composed with other shapes, e.g. `Mixed<Option<Mixed<Vec<&'static T>>>>`
+/// for `Option<Vec<&T>>`.
 pub struct Mixed<T>(std::marker::PhantomData<T>);
 
+/// An iterator over the items of a response, produced by `returns_items`.
+///
+/// Every consumed item is counted, so that the number of consumed items can be verified.
+/// With the `futures` feature enabled, this also implements `Stream`.
+pub struct Items<T> {
+    items: std::vec::IntoIter<T>,
+    consumed: Arc<AtomicUsize>,
+}
+
+impl<T> Items<T> {
+    pub(crate) fn new(items: Vec<T>, consumed: Arc<AtomicUsize>) -> Self {
+        Self {
+            items: items.into_iter(),
+            consumed,
+        }
+    }
+}
+
+impl<T> Iterator for Items<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.items.next()?;
+        self.consumed
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+// Items are never pinned in place
+impl<T> Unpin for Items<T> {}
+
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for Items<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<T>> {
+        std::task::Poll::Ready(self.get_mut().next())
+    }
+}
+
+/// Trait for response types that can be made from a sequence of items, like boxed iterators and streams.
+pub trait FromItems<T> {
+    /// Make the response from the items.
+    fn from_items(items: Items<T>) -> Self;
+}
+
+impl<T> FromItems<T> for Items<T> {
+    fn from_items(items: Items<T>) -> Self {
+        items
+    }
+}
+
+impl<T: 'static> FromItems<T> for Box<dyn Iterator<Item = T>> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(items)
+    }
+}
+
+impl<T: Send + 'static> FromItems<T> for Box<dyn Iterator<Item = T> + Send> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(items)
+    }
+}
+
+impl<T: Send + Sync + 'static> FromItems<T> for Box<dyn Iterator<Item = T> + Send + Sync> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::new(items)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: 'static> FromItems<T> for std::pin::Pin<Box<dyn futures_core::Stream<Item = T>>> {
+    fn from_items(items: Items<T>) -> Self {
+        Box::pin(items)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Send + 'static> FromItems<T>
+    for std::pin::Pin<Box<dyn futures_core::Stream<Item = T> + Send>>
+{
+    fn from_items(items: Items<T>) -> Self {
+        Box::pin(items)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Send + Sync + 'static> FromItems<T>
+    for std::pin::Pin<Box<dyn futures_core::Stream<Item = T> + Send + Sync>>
+{
+    fn from_items(items: Items<T>) -> Self {
+        Box::pin(items)
+    }
+}
+
 type BoxBorrow<T> = Box<dyn Borrow<T> + Send + Sync>;
 
 mod owned {
@@ -304,8 +478,14 @@ mod mixed_vec {
     {
         type Type = Vec<&'u T>;
 
-        fn from_response(_: <Mix<T> as Respond>::Type, _: &'u ValueChain) -> Self::Type {
-            panic!()
+        fn from_response(
+            response: <Mix<T> as Respond>::Type,
+            value_chain: &'u ValueChain,
+        ) -> Self::Type {
+            response
+                .into_iter()
+                .map(|value| value_chain.add(value).as_ref().borrow())
+                .collect()
         }
 
         fn try_from_borrowed_response(
@@ -459,3 +639,280 @@ mixed_tuples!((T0, A0, 0));
 mixed_tuples!((T0, A0, 0), (T1, A1, 1));
 mixed_tuples!((T0, A0, 0), (T1, A1, 1), (T2, A2, 2));
 mixed_tuples!((T0, A0, 0), (T1, A1, 1), (T2, A2, 2), (T3, A3, 3));
+
+// Compositional impls for containers of other shapes, e.g. `Mixed<Option<Mixed<Vec<&'static T>>>>`.
+// These are implemented per element shape, so that they do not overlap with the flat impls above.
+macro_rules! mixed_containers {
+    ($shape:ident, [$($bounds:tt)*], [$($output_bounds:tt)*]) => {
+        impl<X: $($bounds)*> Respond for Mixed<Option<$shape<X>>>
+        where
+            $shape<X>: Respond,
+        {
+            type Type = Option<<$shape<X> as Respond>::Type>;
+        }
+
+        impl<X: $($bounds)*, A0> IntoResponseOnce<Mixed<Option<$shape<X>>>> for Option<A0>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>>,
+        {
+            fn into_response(self) -> <Mixed<Option<$shape<X>>> as Respond>::Type {
+                self.map(A0::into_response)
+            }
+
+            fn into_once_responder<F: MockFn<Response = Mixed<Option<$shape<X>>>>>(self) -> Responder {
+                let response = <Self as IntoResponseOnce<Mixed<Option<$shape<X>>>>>::into_response(self);
+                Responder(DynResponder::new_cell::<F>(response))
+            }
+        }
+
+        impl<X: $($bounds)*, A0> IntoResponseClone<Mixed<Option<$shape<X>>>> for Option<A0>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>> + Clone + Send + Sync + 'static,
+        {
+            fn into_clone_responder<F: MockFn<Response = Mixed<Option<$shape<X>>>>>(self) -> Responder {
+                Responder(DynResponder::new_clone_factory_cell::<F>(move || {
+                    Some(self.clone().map(A0::into_response))
+                }))
+            }
+        }
+
+        impl<'u, X: $($bounds)*, Y: $($output_bounds)*> Output<'u, Mixed<Option<$shape<X>>>> for Mixed<Option<$shape<Y>>>
+        where
+            $shape<X>: Respond,
+            $shape<Y>: Output<'u, $shape<X>>,
+        {
+            type Type = Option<<$shape<Y> as Output<'u, $shape<X>>>::Type>;
+
+            fn from_response(
+                response: <Mixed<Option<$shape<X>>> as Respond>::Type,
+                value_chain: &'u ValueChain,
+            ) -> Self::Type {
+                response.map(|value| <$shape<Y> as Output<'u, $shape<X>>>::from_response(value, value_chain))
+            }
+
+            fn try_from_borrowed_response(
+                response: &'u <Mixed<Option<$shape<X>>> as Respond>::Type,
+            ) -> Result<Self::Type, SignatureError> {
+                response
+                    .as_ref()
+                    .map(<$shape<Y> as Output<'u, $shape<X>>>::try_from_borrowed_response)
+                    .transpose()
+            }
+        }
+
+        impl<X: $($bounds)*> Respond for Mixed<Vec<$shape<X>>>
+        where
+            $shape<X>: Respond,
+        {
+            type Type = Vec<<$shape<X> as Respond>::Type>;
+        }
+
+        impl<X: $($bounds)*, A0> IntoResponseOnce<Mixed<Vec<$shape<X>>>> for Vec<A0>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>>,
+        {
+            fn into_response(self) -> <Mixed<Vec<$shape<X>>> as Respond>::Type {
+                self.into_iter().map(A0::into_response).collect()
+            }
+
+            fn into_once_responder<F: MockFn<Response = Mixed<Vec<$shape<X>>>>>(self) -> Responder {
+                let response = <Self as IntoResponseOnce<Mixed<Vec<$shape<X>>>>>::into_response(self);
+                Responder(DynResponder::new_cell::<F>(response))
+            }
+        }
+
+        impl<X: $($bounds)*, A0> IntoResponseClone<Mixed<Vec<$shape<X>>>> for Vec<A0>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>> + Clone + Send + Sync + 'static,
+        {
+            fn into_clone_responder<F: MockFn<Response = Mixed<Vec<$shape<X>>>>>(self) -> Responder {
+                Responder(DynResponder::new_clone_factory_cell::<F>(move || {
+                    Some(self.iter().cloned().map(A0::into_response).collect())
+                }))
+            }
+        }
+
+        impl<'u, X: $($bounds)*, Y: $($output_bounds)*> Output<'u, Mixed<Vec<$shape<X>>>> for Mixed<Vec<$shape<Y>>>
+        where
+            $shape<X>: Respond,
+            $shape<Y>: Output<'u, $shape<X>>,
+        {
+            type Type = Vec<<$shape<Y> as Output<'u, $shape<X>>>::Type>;
+
+            fn from_response(
+                response: <Mixed<Vec<$shape<X>>> as Respond>::Type,
+                value_chain: &'u ValueChain,
+            ) -> Self::Type {
+                response
+                    .into_iter()
+                    .map(|value| <$shape<Y> as Output<'u, $shape<X>>>::from_response(value, value_chain))
+                    .collect()
+            }
+
+            fn try_from_borrowed_response(
+                response: &'u <Mixed<Vec<$shape<X>>> as Respond>::Type,
+            ) -> Result<Self::Type, SignatureError> {
+                response
+                    .iter()
+                    .map(<$shape<Y> as Output<'u, $shape<X>>>::try_from_borrowed_response)
+                    .collect()
+            }
+        }
+
+        impl<X: $($bounds)*, E: 'static> Respond for Mixed<Result<$shape<X>, E>>
+        where
+            $shape<X>: Respond,
+        {
+            type Type = Result<<$shape<X> as Respond>::Type, E>;
+        }
+
+        impl<X: $($bounds)*, A0, E> IntoResponseOnce<Mixed<Result<$shape<X>, E>>> for Result<A0, E>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>>,
+            E: Send + Sync + 'static,
+        {
+            fn into_response(self) -> <Mixed<Result<$shape<X>, E>> as Respond>::Type {
+                self.map(A0::into_response)
+            }
+
+            fn into_once_responder<F: MockFn<Response = Mixed<Result<$shape<X>, E>>>>(self) -> Responder {
+                let response = <Self as IntoResponseOnce<Mixed<Result<$shape<X>, E>>>>::into_response(self);
+                Responder(DynResponder::new_cell::<F>(response))
+            }
+        }
+
+        impl<X: $($bounds)*, A0, E> IntoResponseClone<Mixed<Result<$shape<X>, E>>> for Result<A0, E>
+        where
+            $shape<X>: Respond,
+            <$shape<X> as Respond>::Type: Send + Sync,
+            A0: IntoResponseOnce<$shape<X>> + Clone + Send + Sync + 'static,
+            E: Clone + Send + Sync + 'static,
+        {
+            fn into_clone_responder<F: MockFn<Response = Mixed<Result<$shape<X>, E>>>>(self) -> Responder {
+                Responder(DynResponder::new_clone_factory_cell::<F>(move || {
+                    Some(self.clone().map(A0::into_response))
+                }))
+            }
+        }
+
+        impl<'u, X: $($bounds)*, Y: $($output_bounds)*, E: 'static> Output<'u, Mixed<Result<$shape<X>, E>>> for Mixed<Result<$shape<Y>, E>>
+        where
+            $shape<X>: Respond,
+            $shape<Y>: Output<'u, $shape<X>>,
+        {
+            type Type = Result<<$shape<Y> as Output<'u, $shape<X>>>::Type, E>;
+
+            fn from_response(
+                response: <Mixed<Result<$shape<X>, E>> as Respond>::Type,
+                value_chain: &'u ValueChain,
+            ) -> Self::Type {
+                response.map(|value| <$shape<Y> as Output<'u, $shape<X>>>::from_response(value, value_chain))
+            }
+
+            fn try_from_borrowed_response(
+                response: &'u <Mixed<Result<$shape<X>, E>> as Respond>::Type,
+            ) -> Result<Self::Type, SignatureError> {
+                match response {
+                    Ok(value) => Ok(Ok(<$shape<Y> as Output<'u, $shape<X>>>::try_from_borrowed_response(value)?)),
+                    // No chance of converting the E into owned here:
+                    Err(_) => Err(SignatureError::OwnershipRequired),
+                }
+            }
+        }
+    };
+}
+
+mixed_containers!(Owned, ['static], ['static]);
+mixed_containers!(Borrowed, [?Sized + 'static], [?Sized + 'static]);
+mixed_containers!(StaticRef, [?Sized + 'static], [?Sized + 'static]);
+mixed_containers!(Mixed, ['static], []);
+
+macro_rules! mixed_maps {
+    ($map:ident, [$($key_bounds:tt)*]) => {
+        impl<K, R> Respond for Mixed<$map<K, R>>
+        where
+            K: $($key_bounds)* + 'static,
+            R: Respond,
+        {
+            type Type = $map<K, <R as Respond>::Type>;
+        }
+
+        impl<K, R, A> IntoResponseOnce<Mixed<$map<K, R>>> for $map<K, A>
+        where
+            K: $($key_bounds)* + Send + Sync + 'static,
+            R: Respond,
+            <R as Respond>::Type: Send + Sync,
+            A: IntoResponseOnce<R>,
+        {
+            fn into_response(self) -> <Mixed<$map<K, R>> as Respond>::Type {
+                self.into_iter()
+                    .map(|(key, value)| (key, value.into_response()))
+                    .collect()
+            }
+
+            fn into_once_responder<F: MockFn<Response = Mixed<$map<K, R>>>>(self) -> Responder {
+                let response = <Self as IntoResponseOnce<Mixed<$map<K, R>>>>::into_response(self);
+                Responder(DynResponder::new_cell::<F>(response))
+            }
+        }
+
+        impl<K, R, A> IntoResponseClone<Mixed<$map<K, R>>> for $map<K, A>
+        where
+            K: $($key_bounds)* + Clone + Send + Sync + 'static,
+            R: Respond,
+            <R as Respond>::Type: Send + Sync,
+            A: IntoResponseOnce<R> + Clone + Send + Sync + 'static,
+        {
+            fn into_clone_responder<F: MockFn<Response = Mixed<$map<K, R>>>>(self) -> Responder {
+                Responder(DynResponder::new_clone_factory_cell::<F>(move || {
+                    Some(
+                        self.iter()
+                            .map(|(key, value)| (key.clone(), value.clone().into_response()))
+                            .collect(),
+                    )
+                }))
+            }
+        }
+
+        impl<'u, K, R, O> Output<'u, Mixed<$map<K, R>>> for Mixed<$map<K, O>>
+        where
+            K: $($key_bounds)* + Clone + 'static,
+            R: Respond,
+            O: Output<'u, R>,
+        {
+            type Type = $map<K, <O as Output<'u, R>>::Type>;
+
+            fn from_response(
+                response: <Mixed<$map<K, R>> as Respond>::Type,
+                value_chain: &'u ValueChain,
+            ) -> Self::Type {
+                response
+                    .into_iter()
+                    .map(|(key, value)| (key, O::from_response(value, value_chain)))
+                    .collect()
+            }
+
+            fn try_from_borrowed_response(
+                response: &'u <Mixed<$map<K, R>> as Respond>::Type,
+            ) -> Result<Self::Type, SignatureError> {
+                response
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), O::try_from_borrowed_response(value)?)))
+                    .collect()
+            }
+        }
+    };
+}
+
+mixed_maps!(HashMap, [Eq + Hash]);
+mixed_maps!(BTreeMap, [Ord]);
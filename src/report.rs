@@ -0,0 +1,315 @@
+use std::io::Write;
+
+use crate::debug;
+use crate::error::MockError;
+use crate::mismatch::{MismatchKind, Mismatches};
+
+/// The environment variable that enables reports, when set to `stderr` or a file path.
+const REPORT_ENV_VAR: &str = "UNIMOCK_REPORT";
+
+/// Where machine-readable reports of mock errors are written.
+///
+/// Each error is written as one JSON object per line.
+/// Configured per [Unimock](crate::Unimock) instance using [with_report](crate::Unimock::with_report),
+/// or for all instances using the `UNIMOCK_REPORT` environment variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReportTarget {
+    /// Write reports to stderr.
+    Stderr,
+    /// Append reports to the given file, creating it if it does not exist.
+    File(std::path::PathBuf),
+}
+
+impl ReportTarget {
+    /// Read the report target from the `UNIMOCK_REPORT` environment variable.
+    pub(crate) fn from_env() -> Option<Self> {
+        match std::env::var_os(REPORT_ENV_VAR) {
+            Some(value) if value == "stderr" => Some(Self::Stderr),
+            Some(value) if !value.is_empty() => Some(Self::File(value.into())),
+            _ => None,
+        }
+    }
+}
+
+/// Write one JSON line per error to the report target.
+///
+/// Reports are written while unimock is about to panic, so IO errors are ignored.
+pub(crate) fn write_report(target: &ReportTarget, path: &str, errors: &[MockError]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    let mut lines = String::new();
+    for error in errors {
+        lines.push_str(&error_json(path, error));
+        lines.push('\n');
+    }
+
+    let _ = match target {
+        ReportTarget::Stderr => std::io::stderr().write_all(lines.as_bytes()),
+        ReportTarget::File(file_path) => std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .and_then(|mut file| file.write_all(lines.as_bytes())),
+    };
+}
+
+fn error_json(path: &str, error: &MockError) -> String {
+    let mut json = JsonObject::new();
+    json.str("error", error_kind(error));
+    if !path.is_empty() {
+        json.str("mock_path", path.trim_end_matches(" -> "));
+    }
+
+    match error {
+        MockError::Downcast { fn_call, pattern }
+        | MockError::NoMatcherFunction { fn_call, pattern }
+        | MockError::NoOutputAvailableForCallPattern { fn_call, pattern }
+        | MockError::CannotReturnValueMoreThanOnce { fn_call, pattern }
+        | MockError::CannotBorrowFromInputs { fn_call, pattern }
+        | MockError::ExplicitPanic {
+            fn_call, pattern, ..
+        } => {
+            fn_call_fields(&mut json, fn_call);
+            pattern_fields(&mut json, pattern);
+        }
+        MockError::NoMockImplementation { fn_call } => {
+            fn_call_fields(&mut json, fn_call);
+        }
+        MockError::NoMatchingCallPatterns {
+            fn_call,
+            closest,
+            mismatches,
+        } => {
+            fn_call_fields(&mut json, fn_call);
+            if let Some(closest) = closest {
+                pattern_fields(&mut json, &closest.pattern);
+            }
+            json.raw("mismatches", mismatches_json(mismatches));
+        }
+        MockError::CallOrderNotMatchedForMockFn {
+            fn_call,
+            actual_call_order,
+            expected,
+        } => {
+            fn_call_fields(&mut json, fn_call);
+            json.num("call_order", actual_call_order.0 + 1);
+            if let Some(expected) = expected {
+                pattern_fields(&mut json, expected);
+            }
+        }
+        MockError::InputsNotMatchedInCallOrder {
+            fn_call,
+            actual_call_order,
+            pattern,
+            mismatches,
+        } => {
+            fn_call_fields(&mut json, fn_call);
+            json.num("call_order", actual_call_order.0 + 1);
+            pattern_fields(&mut json, pattern);
+            json.raw("mismatches", mismatches_json(mismatches));
+        }
+        MockError::MockNeverCalled { name } | MockError::CannotUnmock { name } => {
+            json.str("function", name);
+        }
+        MockError::CallCountNotMatched {
+            name,
+            pattern,
+            expected,
+            actual,
+            ..
+        } => {
+            json.str("function", name);
+            pattern_fields(&mut json, pattern);
+            json.num("expected_calls", expected.0);
+            json.num("actual_calls", actual.0);
+        }
+        MockError::ItemsNotConsumed {
+            name,
+            pattern,
+            expected,
+            consumed,
+        } => {
+            json.str("function", name);
+            pattern_fields(&mut json, pattern);
+            json.num("expected_items", *expected);
+            json.num("consumed_items", *consumed);
+        }
+    }
+
+    // The parts of the message are already limited in size, but may be styled for terminals
+    json.str("message", &strip_ansi(&format!("{path}{error}")));
+    json.finish()
+}
+
+/// A short, stable identifier of the kind of error.
+pub(crate) fn error_kind(error: &MockError) -> &'static str {
+    match error {
+        MockError::Downcast { .. } => "downcast",
+        MockError::NoMockImplementation { .. } => "no_mock_implementation",
+        MockError::NoMatcherFunction { .. } => "no_matcher_function",
+        MockError::NoMatchingCallPatterns { .. } => "no_matching_call_patterns",
+        MockError::NoOutputAvailableForCallPattern { .. } => "no_output_available",
+        MockError::MockNeverCalled { .. } => "mock_never_called",
+        MockError::CallOrderNotMatchedForMockFn { .. } => "call_order_not_matched",
+        MockError::InputsNotMatchedInCallOrder { .. } => "inputs_not_matched_in_call_order",
+        MockError::CannotReturnValueMoreThanOnce { .. } => "cannot_return_value_more_than_once",
+        MockError::CannotBorrowFromInputs { .. } => "cannot_borrow_from_inputs",
+        MockError::CallCountNotMatched { .. } => "call_count_not_matched",
+        MockError::ItemsNotConsumed { .. } => "items_not_consumed",
+        MockError::CannotUnmock { .. } => "cannot_unmock",
+        MockError::ExplicitPanic { .. } => "explicit_panic",
+    }
+}
+
+fn fn_call_fields(json: &mut JsonObject, fn_call: &debug::FnActualCall) {
+    json.str("function", fn_call.name());
+    json.str("inputs", fn_call.inputs_debug());
+    json.str("call_site", &fn_call.call_site().to_string());
+}
+
+fn pattern_fields(json: &mut JsonObject, pattern: &debug::CallPatternDebug) {
+    json.str("pattern", &pattern.to_string());
+    if let Some((file, line)) = pattern.location() {
+        json.str("pattern_location", &format!("{file}:{line}"));
+    }
+}
+
+fn mismatches_json(mismatches: &Mismatches) -> String {
+    let items: Vec<String> = mismatches
+        .iter()
+        .map(|(pat_index, input_index, input_name, mismatch)| {
+            let mut json = JsonObject::new();
+            json.num("pattern_index", pat_index.0);
+            if let Some((file, line)) = mismatches.pattern_location(*pat_index) {
+                json.str("pattern_location", &format!("{file}:{line}"));
+            }
+            json.num("input_index", input_index.0);
+            if let Some(input_name) = input_name {
+                json.str("input_name", input_name);
+            }
+            json.str(
+                "kind",
+                match mismatch.kind {
+                    MismatchKind::Pattern => "pattern",
+                    MismatchKind::Eq => "eq",
+                    MismatchKind::Ne => "ne",
+                    MismatchKind::Regex => "regex",
+                    MismatchKind::Matcher => "matcher",
+                    MismatchKind::Guard => "guard",
+                },
+            );
+            if let Some(actual) = &mismatch.actual {
+                json.str("actual", &mismatches.truncate_value(actual));
+            }
+            json.str("expected", &mismatches.truncate_value(&mismatch.expected));
+            json.finish()
+        })
+        .collect();
+
+    format!("[{}]", items.join(","))
+}
+
+struct JsonObject {
+    buf: String,
+}
+
+impl JsonObject {
+    fn new() -> Self {
+        Self {
+            buf: String::from("{"),
+        }
+    }
+
+    fn str(&mut self, key: &str, value: &str) {
+        self.raw(key, escape(value));
+    }
+
+    fn num(&mut self, key: &str, value: usize) {
+        self.raw(key, value.to_string());
+    }
+
+    fn raw(&mut self, key: &str, value: String) {
+        if self.buf.len() > 1 {
+            self.buf.push(',');
+        }
+        self.buf.push_str(&escape(key));
+        self.buf.push(':');
+        self.buf.push_str(&value);
+    }
+
+    fn finish(mut self) -> String {
+        self.buf.push('}');
+        self.buf
+    }
+}
+
+/// Remove the ANSI escape sequences used for styling terminal output.
+fn strip_ansi(value: &str) -> String {
+    let mut stripped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the sequence up to and including its final byte
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) && c != '[' {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_escape_json_strings() {
+        assert_eq!(r#""a\"b\\c\nd\u001b""#, escape("a\"b\\c\nd\u{1b}"));
+    }
+
+    #[test]
+    fn should_strip_ansi_escape_sequences() {
+        assert_eq!(
+            "Diff < left / right >",
+            strip_ansi(
+                "\x1b[1mDiff\x1b[0m \x1b[31m< left\x1b[0m / \x1b[1;48;5;22;32mright >\x1b[0m"
+            )
+        );
+    }
+
+    #[test]
+    fn should_build_json_objects() {
+        let mut json = JsonObject::new();
+        json.str("function", "Foo::bar");
+        json.num("actual_calls", 2);
+        json.raw("mismatches", "[]".to_string());
+        assert_eq!(
+            r#"{"function":"Foo::bar","actual_calls":2,"mismatches":[]}"#,
+            json.finish()
+        );
+    }
+}
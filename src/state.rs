@@ -1,13 +1,16 @@
+use crate::call_tree::{CallTree, CurrentCall, CurrentCalls, Outcome};
 use crate::debug;
 use crate::error;
 use crate::fn_mocker::{FnMocker, PatternMatchMode};
+use crate::mismatch::{DiffMode, OutputLimits, Verbosity};
+use crate::report::{self, ReportTarget};
 use crate::value_chain::ValueChain;
 use crate::FallbackMode;
 
 use std::any::TypeId;
 use std::collections::HashMap;
-use std::sync::atomic::AtomicUsize;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex};
 use std::thread::ThreadId;
 
 pub(crate) struct SharedState {
@@ -19,8 +22,27 @@ pub(crate) struct SharedState {
     // a function signature needs to *borrow* instead.
     pub value_chain: ValueChain,
 
+    // How mismatched values are compared in mismatch reports
+    pub diff_mode: Mutex<DiffMode>,
+    // How much detail is included in mismatch reports
+    pub verbosity: Mutex<Verbosity>,
+    // Size limits of rendered values in error messages
+    pub output_limits: Mutex<OutputLimits>,
+    // Where machine-readable reports of errors are written, if anywhere
+    pub report_target: Mutex<Option<ReportTarget>>,
+    // The nesting of calls into the instance, when call tree tracing is enabled
+    pub call_tree: Mutex<Option<CallTree>>,
+    // Whether call tree tracing is enabled, checked before locking the call tree
+    call_tree_enabled: AtomicBool,
+
     next_ordered_call_index: AtomicUsize,
+    original_instances: AtomicUsize,
     panic_reasons: Mutex<Vec<error::MockError>>,
+
+    // Path used in error messages when this is the state of a child mock, e.g. `Pool::open_session -> `
+    path: Mutex<String>,
+    // Child mocks handed out by the named functions, verified together with this state
+    children: Mutex<Vec<(&'static str, Arc<SharedState>)>>,
 }
 
 impl SharedState {
@@ -30,8 +52,17 @@ impl SharedState {
             fn_mockers,
             original_thread: std::thread::current().id(),
             value_chain: ValueChain::default(),
+            diff_mode: Mutex::new(DiffMode::default()),
+            verbosity: Mutex::new(Verbosity::default()),
+            output_limits: Mutex::new(OutputLimits::default()),
+            report_target: Mutex::new(ReportTarget::from_env()),
+            call_tree: Mutex::new(None),
+            call_tree_enabled: AtomicBool::new(false),
             next_ordered_call_index: AtomicUsize::new(0),
+            original_instances: AtomicUsize::new(1),
             panic_reasons: Mutex::new(vec![]),
+            path: Mutex::new(String::new()),
+            children: Mutex::new(vec![]),
         }
     }
 
@@ -40,8 +71,29 @@ impl SharedState {
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
     }
 
+    pub fn add_original_instance(&self) {
+        self.original_instances
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Returns the number of original instances that are still alive
+    pub fn release_original_instance(&self) -> usize {
+        self.original_instances
+            .fetch_sub(1, std::sync::atomic::Ordering::SeqCst)
+            - 1
+    }
+
     pub fn prepare_panic(&self, error: error::MockError) -> String {
-        let msg = format!("{error}");
+        let mut msg = format!("{}{error}", self.path());
+        self.report(std::slice::from_ref(&error));
+
+        #[cfg(feature = "tracing")]
+        crate::trace_event::failed(&error);
+
+        self.trace_outcome(|| Outcome::Failed);
+        if let Some(call_tree) = self.call_tree_string() {
+            msg.push_str(&format!("\n{call_tree}"));
+        }
 
         let mut panic_reasons = self.panic_reasons.lock().unwrap();
         panic_reasons.push(error);
@@ -49,8 +101,167 @@ impl SharedState {
         msg
     }
 
-    pub fn clone_panic_reasons(&self) -> Vec<error::MockError> {
-        self.panic_reasons.lock().unwrap().clone()
+    /// Collect the panic reasons of this state and its children, as strings.
+    pub fn collect_panic_reasons(&self, reasons: &mut Vec<String>) {
+        let path = self.path();
+        reasons.extend(
+            self.panic_reasons
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|error| format!("{path}{error}")),
+        );
+
+        for (_, child) in self.children.lock().unwrap().iter() {
+            child.collect_panic_reasons(reasons);
+        }
+    }
+
+    /// Verify the call expectations of this state and its children.
+    pub fn verify(&self, errors: &mut Vec<String>) {
+        let mut mock_errors = Vec::new();
+        for (_, fn_mocker) in self.fn_mockers.iter() {
+            fn_mocker.verify(&mut mock_errors);
+        }
+
+        self.report(&mock_errors);
+
+        let path = self.path();
+        errors.extend(mock_errors.iter().map(|error| format!("{path}{error}")));
+
+        for (_, child) in self.children.lock().unwrap().iter() {
+            child.verify(errors);
+        }
+    }
+
+    /// Register the child mocks of the call patterns, so they get verified together with this state,
+    /// whether or not they are ever handed out.
+    pub fn register_children(&self) {
+        let mut children = self.children.lock().unwrap();
+        for fn_mocker in self.fn_mockers.values() {
+            for call_pattern in &fn_mocker.call_patterns {
+                for child in &call_pattern.children {
+                    children.push((fn_mocker.dyn_mock_fn.name, child.clone()));
+                }
+            }
+        }
+        drop(children);
+
+        self.set_path(self.path());
+    }
+
+    /// Set the path of this state, and of its children below it.
+    fn set_path(&self, path: String) {
+        for (mock_fn_name, child) in self.children.lock().unwrap().iter() {
+            child.set_path(format!("{path}{mock_fn_name} -> "));
+        }
+        *self.path.lock().unwrap() = path;
+    }
+
+    /// Set the report target of this state and its children.
+    pub fn set_report_target(&self, target: Option<ReportTarget>) {
+        for (_, child) in self.children.lock().unwrap().iter() {
+            child.set_report_target(target.clone());
+        }
+        *self.report_target.lock().unwrap() = target;
+    }
+
+    /// Enable call tree tracing.
+    pub fn enable_call_tree(&self) {
+        *self.call_tree.lock().unwrap() = Some(CallTree::default());
+        self.call_tree_enabled
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Whether call tree tracing is enabled.
+    pub fn is_tracing(&self) -> bool {
+        self.call_tree_enabled
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Record a call in the call tree, as made from the innermost current call of the thread or task.
+    ///
+    /// The call becomes the innermost current call, until its position in [CurrentCalls] gets truncated.
+    pub fn trace_enter(
+        &self,
+        call: impl FnOnce() -> String,
+        call_site: &'static std::panic::Location<'static>,
+    ) {
+        if !self.is_tracing() {
+            return;
+        }
+        let state_id = self.id();
+        let parent = CurrentCalls::innermost(state_id).and_then(|current| current.node);
+        let max_input_len = self.output_limits.lock().unwrap().max_input_len;
+        let call = crate::debug::truncate(&call(), max_input_len).into_owned();
+
+        if let Some(call_tree) = self.call_tree.lock().unwrap().as_mut() {
+            let node = call_tree.enter(parent, call, call_site);
+            CurrentCalls::push(CurrentCall { state_id, node });
+        }
+    }
+
+    /// Set the outcome of the innermost current call, if call tree tracing is enabled.
+    pub fn trace_outcome(&self, outcome: impl FnOnce() -> Outcome) {
+        if !self.is_tracing() {
+            return;
+        }
+        if let Some(CurrentCall {
+            node: Some(node), ..
+        }) = CurrentCalls::innermost(self.id())
+        {
+            if let Some(call_tree) = self.call_tree.lock().unwrap().as_mut() {
+                call_tree.set_outcome(node, outcome());
+            }
+        }
+    }
+
+    /// Register the output of the current call at the given position, if call tree tracing is enabled.
+    pub fn trace_exit(&self, position: usize, output: impl FnOnce() -> String) {
+        if !self.is_tracing() {
+            return;
+        }
+        match CurrentCalls::get(position) {
+            Some(CurrentCall {
+                state_id,
+                node: Some(node),
+            }) if state_id == self.id() => {
+                let max_input_len = self.output_limits.lock().unwrap().max_input_len;
+                let output = crate::debug::truncate(&output(), max_input_len).into_owned();
+                if let Some(call_tree) = self.call_tree.lock().unwrap().as_mut() {
+                    call_tree.exit(node, output);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render the call tree, if call tree tracing is enabled.
+    pub fn call_tree_string(&self) -> Option<String> {
+        if !self.is_tracing() {
+            return None;
+        }
+        self.call_tree
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|call_tree| call_tree.to_string())
+    }
+
+    /// Identifies this state among the [CurrentCalls].
+    fn id(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Write the errors to the report target, if configured.
+    fn report(&self, errors: &[error::MockError]) {
+        if let Some(target) = self.report_target.lock().unwrap().as_ref() {
+            report::write_report(target, &self.path(), errors);
+        }
+    }
+
+    fn path(&self) -> String {
+        self.path.lock().unwrap().clone()
     }
 
     pub fn find_ordered_expected_call_pattern_debug(
@@ -0,0 +1,535 @@
+//! Structural comparison of `Debug` representations.
+//!
+//! The `Debug` output of both values is parsed into a tree of structs, tuples, lists and maps,
+//! which are compared recursively, so that only the paths of differing values get reported.
+
+/// Compare two `Debug` representations, returning one line per differing path, e.g. `items[2].price: 10 != 12`.
+///
+/// Returns `None` when either representation could not be parsed, or is nested too deeply.
+pub(crate) fn structural_diff(actual: &str, expected: &str) -> Option<Vec<String>> {
+    let actual = Parser::new(actual).parse_complete()?;
+    let expected = Parser::new(expected).parse_complete()?;
+
+    let mut lines = vec![];
+    diff("", &actual, &expected, &mut lines);
+    Some(lines)
+}
+
+struct Node<'s> {
+    text: &'s str,
+    kind: Kind<'s>,
+}
+
+enum Kind<'s> {
+    /// A leaf value, e.g. a number, a string or a unit variant
+    Atom,
+    /// `Name { field: value, .. }`
+    Struct(&'s str, Vec<(&'s str, Node<'s>)>),
+    /// `Name(value, ..)`, or `(value, ..)` with an empty name
+    Tuple(&'s str, Vec<Node<'s>>),
+    /// `[value, ..]`
+    List(Vec<Node<'s>>),
+    /// `{value, ..}`
+    Set(Vec<Node<'s>>),
+    /// `{key: value, ..}`
+    Map(Vec<(Node<'s>, Node<'s>)>),
+}
+
+fn diff(path: &str, actual: &Node, expected: &Node, lines: &mut Vec<String>) {
+    match (&actual.kind, &expected.kind) {
+        (
+            Kind::Struct(actual_name, actual_fields),
+            Kind::Struct(expected_name, expected_fields),
+        ) if actual_name == expected_name => {
+            for (name, expected) in expected_fields {
+                let field_path = join_field(path, name);
+                match actual_fields
+                    .iter()
+                    .find(|(actual_name, _)| actual_name == name)
+                {
+                    Some((_, actual)) => diff(&field_path, actual, expected, lines),
+                    None => push_line(&field_path, None, Some(expected), lines),
+                }
+            }
+            for (name, actual) in actual_fields {
+                if !expected_fields
+                    .iter()
+                    .any(|(expected_name, _)| expected_name == name)
+                {
+                    push_line(&join_field(path, name), Some(actual), None, lines);
+                }
+            }
+        }
+        (Kind::Tuple(actual_name, actual_elems), Kind::Tuple(expected_name, expected_elems))
+            if actual_name == expected_name && actual_elems.len() == expected_elems.len() =>
+        {
+            for (index, (actual, expected)) in actual_elems.iter().zip(expected_elems).enumerate() {
+                diff(
+                    &join_field(path, &index.to_string()),
+                    actual,
+                    expected,
+                    lines,
+                );
+            }
+        }
+        (Kind::List(actual_elems), Kind::List(expected_elems)) => {
+            let len = actual_elems.len().max(expected_elems.len());
+            for index in 0..len {
+                let elem_path = format!("{path}[{index}]");
+                match (actual_elems.get(index), expected_elems.get(index)) {
+                    (Some(actual), Some(expected)) => diff(&elem_path, actual, expected, lines),
+                    (actual, expected) => push_line(&elem_path, actual, expected, lines),
+                }
+            }
+        }
+        (Kind::Set(actual_elems), Kind::Set(expected_elems)) => {
+            // Sets are unordered, so elements are matched by their rendering instead of their position
+            let mut unmatched_actual: Vec<&Node> = actual_elems.iter().collect();
+            let mut unmatched_expected = vec![];
+            for expected in expected_elems {
+                match unmatched_actual
+                    .iter()
+                    .position(|actual| actual.to_string() == expected.to_string())
+                {
+                    Some(index) => {
+                        unmatched_actual.remove(index);
+                    }
+                    None => unmatched_expected.push(expected),
+                }
+            }
+            for expected in unmatched_expected {
+                push_line(path, None, Some(expected), lines);
+            }
+            for actual in unmatched_actual {
+                push_line(path, Some(actual), None, lines);
+            }
+        }
+        (Kind::Map(actual_entries), Kind::Map(expected_entries)) => {
+            let find = |entries: &'_ [(Node<'_>, Node<'_>)], key: &str| {
+                entries
+                    .iter()
+                    .position(|(entry_key, _)| entry_key.text == key)
+            };
+
+            for (key, expected) in expected_entries {
+                let entry_path = format!("{path}[{}]", key.text);
+                match find(actual_entries, key.text) {
+                    Some(index) => diff(&entry_path, &actual_entries[index].1, expected, lines),
+                    None => push_line(&entry_path, None, Some(expected), lines),
+                }
+            }
+            for (key, actual) in actual_entries {
+                if find(expected_entries, key.text).is_none() {
+                    push_line(&format!("{path}[{}]", key.text), Some(actual), None, lines);
+                }
+            }
+        }
+        _ => {
+            let (actual, expected) = (actual.to_string(), expected.to_string());
+            if actual != expected {
+                push_line_str(path, &actual, &expected, lines);
+            }
+        }
+    }
+}
+
+fn join_field(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+fn push_line(path: &str, actual: Option<&Node>, expected: Option<&Node>, lines: &mut Vec<String>) {
+    let render = |node: Option<&Node>| match node {
+        Some(node) => node.to_string(),
+        None => "<missing>".to_string(),
+    };
+    push_line_str(path, &render(actual), &render(expected), lines);
+}
+
+fn push_line_str(path: &str, actual: &str, expected: &str, lines: &mut Vec<String>) {
+    if path.is_empty() {
+        lines.push(format!("{actual} != {expected}"));
+    } else {
+        lines.push(format!("{path}: {actual} != {expected}"));
+    }
+}
+
+/// Renders the node on a single line, like `{:?}`
+impl<'s> std::fmt::Display for Node<'s> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn join<T>(
+            f: &mut std::fmt::Formatter<'_>,
+            items: &[T],
+            write_item: impl Fn(&mut std::fmt::Formatter<'_>, &T) -> std::fmt::Result,
+        ) -> std::fmt::Result {
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write_item(f, item)?;
+            }
+            Ok(())
+        }
+
+        match &self.kind {
+            Kind::Atom => write!(f, "{}", self.text),
+            Kind::Struct(name, fields) if fields.is_empty() => write!(f, "{name} {{}}"),
+            Kind::Struct(name, fields) => {
+                write!(f, "{name} {{ ")?;
+                join(f, fields, |f, (name, value)| write!(f, "{name}: {value}"))?;
+                write!(f, " }}")
+            }
+            Kind::Tuple(name, elems) => {
+                write!(f, "{name}(")?;
+                join(f, elems, |f, elem| write!(f, "{elem}"))?;
+                write!(f, ")")
+            }
+            Kind::List(elems) => {
+                write!(f, "[")?;
+                join(f, elems, |f, elem| write!(f, "{elem}"))?;
+                write!(f, "]")
+            }
+            Kind::Set(elems) => {
+                write!(f, "{{")?;
+                join(f, elems, |f, elem| write!(f, "{elem}"))?;
+                write!(f, "}}")
+            }
+            Kind::Map(entries) => {
+                write!(f, "{{")?;
+                join(f, entries, |f, (key, value)| write!(f, "{key}: {value}"))?;
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// The maximum nesting of parsed values.
+///
+/// Deeper values are not parsed, which bounds the recursion of both parsing and diffing.
+const MAX_DEPTH: usize = 64;
+
+/// A parser for the output of `{:?}` and `{:#?}`.
+struct Parser<'s> {
+    src: &'s str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn new(src: &'s str) -> Self {
+        Self {
+            src,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn parse_complete(mut self) -> Option<Node<'s>> {
+        let node = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos == self.src.len() {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self, c: char) {
+        self.pos += c.len_utf8();
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? == expected {
+            self.bump(expected);
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.bump(c);
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<Node<'s>> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let node = self.parse_value_inner();
+        self.depth -= 1;
+        node
+    }
+
+    fn parse_value_inner(&mut self) -> Option<Node<'s>> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        let kind = match self.peek()? {
+            quote @ ('"' | '\'') => {
+                self.quoted(quote)?;
+                Kind::Atom
+            }
+            '(' => Kind::Tuple("", self.seq('(', ')')?),
+            '[' => Kind::List(self.seq('[', ']')?),
+            '{' => self.braced()?,
+            _ => {
+                let name = self.atom()?;
+                let atom_end = self.pos;
+                self.skip_whitespace();
+                match self.peek() {
+                    Some('{') => Kind::Struct(name, self.fields()?),
+                    Some('(') => Kind::Tuple(name, self.seq('(', ')')?),
+                    _ => {
+                        self.pos = atom_end;
+                        Kind::Atom
+                    }
+                }
+            }
+        };
+
+        Some(Node {
+            text: self.src[start..self.pos].trim(),
+            kind,
+        })
+    }
+
+    fn quoted(&mut self, quote: char) -> Option<()> {
+        self.bump(quote);
+        loop {
+            match self.peek()? {
+                '\\' => {
+                    self.bump('\\');
+                    let escaped = self.peek()?;
+                    self.bump(escaped);
+                }
+                c if c == quote => {
+                    self.bump(c);
+                    return Some(());
+                }
+                c => self.bump(c),
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Option<&'s str> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                ',' | '(' | ')' | '[' | ']' | '{' | '}' => break,
+                ':' if !self.rest().starts_with("::") && !self.src[..self.pos].ends_with(':') => {
+                    break
+                }
+                c if c.is_whitespace() => break,
+                c => self.bump(c),
+            }
+        }
+
+        if self.pos > start {
+            Some(&self.src[start..self.pos])
+        } else {
+            None
+        }
+    }
+
+    /// A comma-separated sequence of values
+    fn seq(&mut self, open: char, close: char) -> Option<Vec<Node<'s>>> {
+        self.expect(open)?;
+        let mut elems = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == close {
+                self.bump(close);
+                return Some(elems);
+            }
+            elems.push(self.parse_value()?);
+            self.end_of_elem(close)?;
+        }
+    }
+
+    /// Fields of a struct, possibly non-exhaustive
+    fn fields(&mut self) -> Option<Vec<(&'s str, Node<'s>)>> {
+        self.expect('{')?;
+        let mut fields = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == '}' {
+                self.bump('}');
+                return Some(fields);
+            }
+            if self.rest().starts_with("..") {
+                self.pos += 2;
+            } else {
+                let name = self.atom()?;
+                self.expect(':')?;
+                fields.push((name, self.parse_value()?));
+            }
+            self.end_of_elem('}')?;
+        }
+    }
+
+    /// A map or a set
+    fn braced(&mut self) -> Option<Kind<'s>> {
+        self.expect('{')?;
+        let mut entries = vec![];
+        let mut elems = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == '}' {
+                self.bump('}');
+                break;
+            }
+            let value = self.parse_value()?;
+            self.skip_whitespace();
+            if elems.is_empty() && self.peek()? == ':' {
+                self.bump(':');
+                entries.push((value, self.parse_value()?));
+            } else if entries.is_empty() {
+                elems.push(value);
+            } else {
+                return None;
+            }
+            self.end_of_elem('}')?;
+        }
+
+        Some(if elems.is_empty() {
+            Kind::Map(entries)
+        } else {
+            Kind::Set(elems)
+        })
+    }
+
+    /// After an element there is either a comma, or the closing delimiter (which is not consumed)
+    fn end_of_elem(&mut self, close: char) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            ',' => {
+                self.bump(',');
+                Some(())
+            }
+            c if c == close => Some(()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Order {
+        id: u32,
+        customer: Customer,
+        items: Vec<Item>,
+        notes: Option<String>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Customer {
+        name: String,
+        tags: std::collections::BTreeMap<&'static str, i32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Item(&'static str, u32);
+
+    fn order() -> Order {
+        Order {
+            id: 1,
+            customer: Customer {
+                name: "alice".to_string(),
+                tags: [("vip", 1)].into_iter().collect(),
+            },
+            items: vec![Item("apple", 10), Item("pear", 12)],
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn should_report_differing_paths_only() {
+        let actual = order();
+        let mut expected = order();
+        expected.customer.name = "bob".to_string();
+        expected.customer.tags.insert("new", 2);
+        expected.items[1].1 = 15;
+        expected.items.push(Item("plum", 3));
+        expected.notes = Some("fragile, \"handle\" {with} care".to_string());
+
+        for format in [
+            |order: &Order| format!("{order:?}"),
+            |order: &Order| format!("{order:#?}"),
+        ] {
+            assert_eq!(
+                Some(vec![
+                    "customer.name: \"alice\" != \"bob\"".to_string(),
+                    "customer.tags[\"new\"]: <missing> != 2".to_string(),
+                    "items[1].1: 12 != 15".to_string(),
+                    "items[2]: <missing> != Item(\"plum\", 3)".to_string(),
+                    "notes: None != Some(\"fragile, \\\"handle\\\" {with} care\")".to_string(),
+                ]),
+                structural_diff(&format(&actual), &format(&expected))
+            );
+        }
+    }
+
+    #[test]
+    fn should_compare_atoms() {
+        assert_eq!(Some(vec!["1 != 2".to_string()]), structural_diff("1", "2"));
+        assert_eq!(
+            Some(vec!["Foo::Bar != Foo::Baz".to_string()]),
+            structural_diff("Foo::Bar", "Foo::Baz")
+        );
+        assert_eq!(Some(vec![]), structural_diff("[1, 2]", "[1, 2]"));
+    }
+
+    #[test]
+    fn should_compare_sets_unordered() {
+        assert_eq!(Some(vec![]), structural_diff("{1, 2, 3}", "{3, 1, 2}"));
+        assert_eq!(
+            Some(vec![
+                "tags: <missing> != \"c\"".to_string(),
+                "tags: \"b\" != <missing>".to_string(),
+            ]),
+            structural_diff(
+                "Foo { tags: {\"a\", \"b\"} }",
+                "Foo { tags: {\"c\", \"a\"} }"
+            )
+        );
+    }
+
+    #[test]
+    fn should_not_parse_deeply_nested_values() {
+        let nested = format!("{}{}", "[".repeat(2000), "]".repeat(2000));
+        assert!(structural_diff(&nested, &nested).is_none());
+
+        let nested = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert_eq!(Some(vec![]), structural_diff(&nested, &nested));
+    }
+
+    #[test]
+    fn should_not_parse_arbitrary_debug_output() {
+        assert!(structural_diff("hello world", "hello").is_none());
+        assert!(structural_diff("Foo { a: 1", "Foo { a: 1 }").is_none());
+    }
+}
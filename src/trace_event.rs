@@ -0,0 +1,55 @@
+use crate::debug;
+use crate::error::MockError;
+use crate::report;
+
+/// The target of all events emitted by unimock.
+const TARGET: &str = "unimock";
+
+/// How a call into a [Unimock](crate::Unimock) instance was handled.
+pub(crate) enum CallOutcome<'a> {
+    Answered(&'a debug::CallPatternDebug),
+    Unmocked,
+}
+
+/// Whether a subscriber is interested in mock events, so that inputs are only rendered when needed.
+pub(crate) fn enabled() -> bool {
+    tracing::enabled!(target: TARGET, tracing::Level::DEBUG)
+}
+
+/// Emit an event for a call that was answered by a call pattern, or passed on to the unmock function.
+pub(crate) fn call(fn_call: &debug::FnActualCall, outcome: CallOutcome) {
+    match outcome {
+        CallOutcome::Answered(pattern) => tracing::debug!(
+            target: TARGET,
+            function = fn_call.name(),
+            inputs = fn_call.inputs_debug(),
+            pattern = %pattern,
+            outcome = "answered",
+            "mocked call answered"
+        ),
+        CallOutcome::Unmocked => tracing::debug!(
+            target: TARGET,
+            function = fn_call.name(),
+            inputs = fn_call.inputs_debug(),
+            outcome = "unmocked",
+            "mocked call unmocked"
+        ),
+    }
+}
+
+/// Emit an event for a call that failed with the given error.
+pub(crate) fn failed(error: &MockError) {
+    let Some(fn_call) = error.fn_call() else {
+        return;
+    };
+
+    tracing::debug!(
+        target: TARGET,
+        function = fn_call.name(),
+        inputs = fn_call.inputs_debug(),
+        pattern = error.pattern().map(tracing::field::display),
+        outcome = "failed",
+        error = report::error_kind(error),
+        "mocked call failed"
+    );
+}
@@ -11,6 +11,7 @@ pub struct ValueChain {
 }
 
 impl ValueChain {
+    /// Add a value to the chain, and get a reference to it that lives as long as the chain itself.
     pub fn add<T: Any + Send + Sync>(&self, value: T) -> &T {
         let node = self.push_node(Node::new(value));
 
@@ -0,0 +1,108 @@
+use unimock::matchers::*;
+use unimock::*;
+
+#[unimock(api=RepoMock)]
+trait Repo {
+    fn find(&self, id: i32, name: String, filter: &str) -> i32;
+    fn count(&self, table: &str) -> usize;
+    fn ping(&self) -> bool;
+}
+
+#[test]
+fn should_match_typed_args() {
+    let u = Unimock::new((
+        RepoMock::find
+            .each_call(&args((eq(1), any(), pred(|s: &str| s.len() > 3))))
+            .returns(1),
+        RepoMock::find
+            .each_call(&args((any(), pred(|name: &str| name.is_empty()), any())))
+            .returns(2),
+        RepoMock::find
+            .each_call(&args((any(), any(), any())))
+            .returns(3),
+    ));
+
+    assert_eq!(1, u.find(1, "x".to_string(), "long"));
+    assert_eq!(2, u.find(1, "".to_string(), "abc"));
+    assert_eq!(3, u.find(2, "x".to_string(), "long"));
+}
+
+#[test]
+fn should_match_single_and_no_args() {
+    let u = Unimock::new((
+        RepoMock::count
+            .each_call(&args((eq("users"),)))
+            .returns(10_usize),
+        RepoMock::count
+            .each_call(&args((starts_with("tmp_"),)))
+            .returns(0_usize),
+        RepoMock::ping.next_call(&args(())).returns(true),
+    ));
+
+    assert_eq!(10, u.count("users"));
+    assert_eq!(0, u.count("tmp_users"));
+    assert!(u.ping());
+}
+
+#[test]
+fn should_build_args_from_a_table() {
+    let table = [("users", 1_usize), ("groups", 2)];
+
+    let u = Unimock::new(RepoMock::count.stub(|each| {
+        for (name, count) in table {
+            each.call(&args((eq(name),))).returns(count);
+        }
+    }));
+
+    assert_eq!(1, u.count("users"));
+    assert_eq!(2, u.count("groups"));
+}
+
+#[test]
+#[should_panic(
+    expected = "Repo::find(id: 1, name: \"x\", filter: \"ab\"): No matching call patterns. \nMatcher mismatch for input `filter`:\nActual value did not satisfy `pred(..)`:\n\"ab\"\n"
+)]
+fn should_report_mismatched_args() {
+    let u = Unimock::new(
+        RepoMock::find
+            .each_call(&args((eq(1), any(), pred(|s: &str| s.len() > 3))))
+            .returns(1),
+    );
+
+    u.find(1, "x".to_string(), "ab");
+}
+
+#[test]
+#[should_panic(
+    expected = "Repo::count(table: \"a\"): Method invoked in the correct order (1), but inputs didn't match Repo::count(eq(\"b\")) at tests/it/args.rs:82."
+)]
+fn should_use_described_args_as_pattern_debug() {
+    let u = Unimock::new(
+        RepoMock::count
+            .next_call(&args((eq("b"),)))
+            .returns(1_usize),
+    );
+
+    u.count("a");
+}
+
+pub struct Opaque(i32);
+
+#[unimock(api=SinkMock)]
+trait Sink {
+    fn put(&self, value: Opaque);
+}
+
+#[test]
+#[should_panic(
+    expected = "Sink::put(value: ?): No matching call patterns. \nMatcher mismatch for input `value`:\nActual value did not satisfy `pred(..)`, but can't display diagnostics because the type is likely missing #[derive(Debug)].\n"
+)]
+fn should_report_mismatched_args_without_debug() {
+    let u = Unimock::new(
+        SinkMock::put
+            .each_call(&args((pred(|value: &Opaque| value.0 > 0),)))
+            .returns(()),
+    );
+
+    u.put(Opaque(0));
+}
@@ -282,7 +282,9 @@ mod no_debug {
     }
 
     #[test]
-    #[should_panic(expected = "VeryPrimitive::primitive(?, \"\"): No matching call patterns.")]
+    #[should_panic(
+        expected = "VeryPrimitive::primitive(a: ?, b: \"\"): No matching call patterns."
+    )]
     fn should_format_non_debug_input_with_a_question_mark() {
         Unimock::new(VeryPrimitiveMock::primitive.stub(|each| {
             each.call(matching!(PrimitiveEnum::Bar, _))
@@ -404,7 +406,7 @@ mod custom_api_module {
 
     #[test]
     #[should_panic(
-        expected = "Single::func: Expected Single::func(_) at tests/it/basic.rs:412 to match exactly 1 call, but it actually matched no calls.\nMock for Single::func was never called. Dead mocks should be removed."
+        expected = "Single::func: Expected Single::func(_) at tests/it/basic.rs:414 to match exactly 1 call, but it actually matched no calls.\nMock for Single::func was never called. Dead mocks should be removed."
     )]
     fn test_without_module() {
         Unimock::new(
@@ -632,7 +634,7 @@ mod responders_in_series {
 
     #[test]
     #[should_panic(
-        expected = "Series::series: Expected Series::series() at tests/it/basic.rs:608 to match at least 4 calls, but it actually matched 2 calls."
+        expected = "Series::series: Expected Series::series() at tests/it/basic.rs:610 to match at least 4 calls, but it actually matched 2 calls."
     )]
     fn series_not_fully_generated_should_panic() {
         let b = Unimock::new(clause());
@@ -786,3 +788,215 @@ fn fn_cfg_attrs() {
     let u = Unimock::new(TraitMock::a.next_call(matching!()).returns(0));
     u.a();
 }
+
+mod destructuring_params {
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct Request {
+        id: i32,
+        body: String,
+    }
+
+    #[unimock(api = Mock, unmock_with=[add_real, _])]
+    trait Trait {
+        fn add(&self, (x, y): (i32, i32)) -> i32 {
+            x + y
+        }
+
+        fn handle(&self, Request { id, .. }: Request, _: bool) -> i32 {
+            id
+        }
+    }
+
+    fn add_real(_: &impl std::any::Any, (x, y): (i32, i32)) -> i32 {
+        x + y
+    }
+
+    #[test]
+    fn destructured_params_are_mockable() {
+        let u = Unimock::new((
+            Mock::add.next_call(matching!((1, 2))).returns(42),
+            Mock::handle
+                .next_call(matching!(Request { id: 1, .. }, true))
+                .returns(7),
+        ));
+
+        assert_eq!(42, u.add((1, 2)));
+        assert_eq!(
+            7,
+            u.handle(
+                Request {
+                    id: 1,
+                    body: "body".to_string()
+                },
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn destructured_params_have_no_input_names() {
+        assert_eq!(&[""], <Mock::add as MockFn>::INPUT_NAMES);
+        assert_eq!(&["", ""], <Mock::handle as MockFn>::INPUT_NAMES);
+    }
+
+    #[test]
+    fn destructured_params_can_be_unmocked() {
+        let u = Unimock::new(Mock::add.next_call(matching!(_)).unmocked());
+
+        assert_eq!(3, u.add((1, 2)));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Trait::handle(Request { id: 2, body: \"body\" }, false): Method invoked in the correct order (1), but inputs didn't match"
+    )]
+    fn destructured_params_are_debugged() {
+        Unimock::new(
+            Mock::handle
+                .next_call(matching!(Request { id: 1, .. }, _))
+                .returns(7),
+        )
+        .handle(
+            Request {
+                id: 2,
+                body: "body".to_string(),
+            },
+            false,
+        );
+    }
+}
+
+mod method_attributes {
+    use super::*;
+
+    #[unimock(api = Mock)]
+    trait Trait {
+        #[unimock(name = renamed)]
+        fn original(&self) -> i32;
+
+        #[unimock(skip)]
+        fn provided(&self) -> i32 {
+            self.original() + 1
+        }
+    }
+
+    #[unimock(api = [RenamedFlat, Other])]
+    trait FlatTrait {
+        #[unimock(name = ExplicitFlat)]
+        fn a(&self) -> i32;
+        fn b(&self) -> i32;
+    }
+
+    #[test]
+    fn renamed_and_skipped_methods() {
+        let u = Unimock::new(Mock::renamed.next_call(matching!()).returns(1));
+        assert_eq!(2, u.provided());
+    }
+
+    #[test]
+    fn renamed_flattened_method() {
+        let u = Unimock::new((
+            ExplicitFlat.next_call(matching!()).returns(1),
+            Other.next_call(matching!()).returns(2),
+        ));
+        assert_eq!(3, u.a() + u.b());
+    }
+}
+
+mod borrowing_from_inputs {
+    use unimock::*;
+
+    #[unimock(api=ParserMock)]
+    trait Parser {
+        fn first_token<'a>(&self, s: &'a str) -> &'a str;
+        fn prefix<'a>(&self, s: &'a str, len: usize) -> &'a str;
+        fn longest<'a>(&self, a: &'a str, b: &'a str) -> &'a str;
+        fn unrelated<'a>(&self, a: &'a str, b: &str) -> &'a str;
+    }
+
+    #[test]
+    fn answers_borrowing_from_single_input() {
+        let u = Unimock::new(
+            ParserMock::first_token
+                .each_call(matching!(_))
+                .answers_borrowing(|borrowed| borrowed.inputs.split(' ').next().unwrap()),
+        );
+
+        let input = String::from("let x = 42");
+        assert_eq!("let", u.first_token(&input));
+        assert_eq!("x", u.first_token(&input[4..]));
+    }
+
+    #[test]
+    fn answers_borrowing_from_several_inputs() {
+        let u = Unimock::new((
+            ParserMock::prefix
+                .each_call(matching!(_, _))
+                .answers_borrowing(|borrowed| &borrowed.inputs.0[..borrowed.inputs.1]),
+            ParserMock::longest
+                .each_call(matching!(_, _))
+                .answers_borrowing(|borrowed| {
+                    let (a, b) = borrowed.inputs;
+                    if a.len() >= b.len() {
+                        a
+                    } else {
+                        b
+                    }
+                }),
+        ));
+
+        assert_eq!("abc", u.prefix("abcdef", 3));
+        assert_eq!("abcd", u.longest("ab", "abcd"));
+    }
+
+    #[test]
+    fn static_response_still_works() {
+        let u = Unimock::new(
+            ParserMock::first_token
+                .next_call(matching!("a b"))
+                .returns("static"),
+        );
+
+        assert_eq!("static", u.first_token("a b"));
+    }
+
+    #[test]
+    fn unrelated_input_lifetimes_can_return_static_ref() {
+        let u = Unimock::new(
+            ParserMock::unrelated
+                .next_call(matching!(_, _))
+                .returns("static"),
+        );
+
+        assert_eq!("static", u.unrelated("a", "b"));
+    }
+}
+
+mod manual_mock_fn {
+    use unimock::*;
+
+    #[allow(non_camel_case_types)]
+    struct double;
+
+    impl MockFn for double {
+        type Inputs<'i> = i32;
+        type Response = output::Owned<i32>;
+        type Output<'u> = output::Owned<i32>;
+        const NAME: &'static str = "double";
+
+        fn debug_inputs(input: &Self::Inputs<'_>) -> String {
+            format!("({input:?})")
+        }
+    }
+
+    #[test]
+    fn only_needs_the_required_items() {
+        assert!(<double as MockFn>::INPUT_NAMES.is_empty());
+
+        let u = Unimock::new(double.each_call(matching!(1)).returns(2));
+
+        assert_eq!(2, macro_api::eval::<double>(&u, 1).unwrap(&u));
+    }
+}
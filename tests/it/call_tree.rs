@@ -0,0 +1,147 @@
+use unimock::*;
+
+#[unimock(api=FactorialMock, unmock_with=[my_factorial(self, input)])]
+trait Factorial {
+    fn factorial(&self, input: u32) -> u32;
+}
+
+fn my_factorial(f: &impl Factorial, input: u32) -> u32 {
+    f.factorial(input - 1) * input
+}
+
+#[test]
+fn should_print_call_tree_of_nested_calls_on_failure() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(FactorialMock::factorial.stub(|each| {
+            each.call(matching!(1)).returns(1_u32);
+            each.call(matching!(4 | 2)).unmocked();
+        }))
+        .with_call_tree();
+
+        assert_eq!(2, u.factorial(2));
+        u.factorial(4);
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+    let tree = &msg[msg.find("Call tree:").unwrap()..];
+    let lines: Vec<&str> = tree.lines().collect();
+
+    assert_eq!(5, lines.len(), "{tree}");
+    assert_eq!(
+        "  Factorial::factorial(input: 2) at tests/it/call_tree.rs:21:25 [unmocked] -> 2",
+        lines[1]
+    );
+    assert!(lines[2].starts_with("    Factorial::factorial(input: 1) at tests/it/call_tree.rs:9:7 [mocked by Factorial::factorial(1) at tests/it/call_tree.rs:"));
+    assert!(lines[2].ends_with("] -> 1"));
+    assert_eq!(
+        "  Factorial::factorial(input: 4) at tests/it/call_tree.rs:22:11 [unmocked]",
+        lines[3]
+    );
+    assert_eq!(
+        "    Factorial::factorial(input: 3) at tests/it/call_tree.rs:9:7 [failed]",
+        lines[4]
+    );
+}
+
+#[test]
+fn should_not_print_call_tree_by_default() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(FactorialMock::factorial.each_call(matching!(2)).unmocked());
+        u.factorial(2);
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(!msg.contains("Call tree:"));
+}
+
+#[unimock(api=FactMock, unmock_with=[my_fact(self, n)])]
+trait Fact {
+    fn fact(&self, n: u64) -> u64;
+}
+
+fn my_fact(f: &impl Fact, n: u64) -> u64 {
+    if n == 0 {
+        1
+    } else {
+        f.fact(n - 1) * n
+    }
+}
+
+/// Checks that every call is nested right under the call it was made from, i.e. `fact(n)` under `fact(n + 1)`.
+fn assert_nested_by_input(tree: &str, top_input: usize) {
+    for line in tree.lines().skip(1) {
+        if line.trim_start().starts_with('(') {
+            continue;
+        }
+        let depth = (line.len() - line.trim_start().len()) / 2 - 1;
+        let input_start = line.find("n: ").unwrap() + 3;
+        let input_end = input_start + line[input_start..].find(')').unwrap();
+        let input: usize = line[input_start..input_end].parse().unwrap();
+
+        assert_eq!(top_input - depth, input, "{line}");
+    }
+}
+
+#[test]
+fn should_not_nest_concurrent_calls_from_threads_into_each_other() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new_partial(
+            FactMock::fact
+                .each_call(matching!(100))
+                .returns(0_u64)
+                .once(),
+        )
+        .with_call_tree();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..200 {
+                        assert_eq!(720, u.fact(6));
+                    }
+                });
+            }
+        });
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+    let tree = &msg[msg.find("Call tree:").unwrap()..];
+
+    assert_nested_by_input(tree, 6);
+    assert_eq!(1002, tree.lines().count(), "the tree should be capped");
+    assert!(tree.ends_with("  (4600 more calls not recorded)\n"));
+}
+
+#[unimock(api=AsyncFactMock, unmock_with=[my_async_fact(self, n)])]
+#[async_trait::async_trait]
+trait AsyncFact {
+    async fn async_fact(&self, n: u64) -> u64;
+}
+
+async fn my_async_fact(f: &impl AsyncFact, n: u64) -> u64 {
+    tokio::task::yield_now().await;
+    if n == 0 {
+        1
+    } else {
+        f.async_fact(n - 1).await * n
+    }
+}
+
+#[tokio::test]
+async fn should_not_nest_interleaved_futures_into_each_other() {
+    let u = Unimock::new_partial(
+        AsyncFactMock::async_fact
+            .each_call(matching!(100))
+            .returns(0_u64)
+            .once(),
+    )
+    .with_call_tree();
+
+    let (a, b) = futures::join!(u.async_fact(3), u.async_fact(3));
+    assert_eq!((6, 6), (a, b));
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(u)));
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+    let tree = &msg[msg.find("Call tree:").unwrap()..];
+
+    assert_nested_by_input(tree, 3);
+    assert_eq!(9, tree.lines().count(), "{tree}");
+}
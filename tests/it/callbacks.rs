@@ -0,0 +1,128 @@
+use unimock::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    A,
+    B,
+}
+
+#[unimock(api=SubscriberMock)]
+trait Subscriber {
+    fn subscribe(&self, on_event: impl Fn(Event) + Send + 'static);
+}
+
+#[unimock(api=FilterMock)]
+trait Filter {
+    fn filter(&self, name: &str, predicate: impl Fn(&str) -> bool) -> Vec<String>;
+}
+
+pub struct Tx {
+    id: i32,
+}
+
+#[unimock(api=DbMock)]
+trait Db {
+    fn with_tx<R: 'static>(&self, f: impl FnOnce(&Tx) -> R) -> R;
+}
+
+#[test]
+fn invokes_fn_callback() {
+    let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let deps = Unimock::new(
+        SubscriberMock::subscribe
+            .next_call(matching!(_))
+            .invokes_arg(|on_event| {
+                on_event(Event::A);
+                on_event(Event::B);
+            }),
+    );
+
+    let events_clone = events.clone();
+    deps.subscribe(move |event| events_clone.lock().unwrap().push(event));
+
+    assert_eq!(vec![Event::A, Event::B], *events.lock().unwrap());
+}
+
+#[test]
+fn callback_with_borrowed_argument_among_other_inputs() {
+    let deps = Unimock::new(FilterMock::filter.next_call(matching!("names", _)).answers(
+        |(_, predicate)| {
+            ["alice", "bob", "anna"]
+                .into_iter()
+                .filter(|name| predicate(name))
+                .map(String::from)
+                .collect::<Vec<_>>()
+        },
+    ));
+
+    assert_eq!(
+        vec!["alice".to_string(), "anna".to_string()],
+        deps.filter("names", |name| name.starts_with('a'))
+    );
+}
+
+#[test]
+fn invokes_generic_fn_once_callback() {
+    let deps = Unimock::new((
+        DbMock::with_tx
+            .with_types::<i32>()
+            .next_call(matching!(_))
+            .invokes_arg(|f| f(&Tx { id: 42 })),
+        DbMock::with_tx
+            .with_types::<String>()
+            .next_call(matching!(_))
+            .invokes_arg(|f| f(&Tx { id: 7 })),
+    ));
+
+    assert_eq!(43, deps.with_tx(|tx| tx.id + 1));
+    assert_eq!("tx 7", deps.with_tx(|tx| format!("tx {}", tx.id)));
+}
+
+#[test]
+fn invokes_callback_among_other_inputs() {
+    let deps = Unimock::new(
+        FilterMock::filter
+            .next_call(matching!("names", _))
+            .invokes_arg(|predicate| {
+                ["alice", "bob"]
+                    .into_iter()
+                    .filter(|name| predicate(name))
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            }),
+    );
+
+    assert_eq!(
+        vec!["bob".to_string()],
+        deps.filter("names", |name| name == "bob")
+    );
+}
+
+#[unimock(api=NotifierMock, unmock_with=[notify_all])]
+trait Notifier {
+    fn notify(&self, count: i32, on_event: impl Fn(Event) + Send + 'static) -> i32;
+}
+
+fn notify_all(
+    _: &impl std::any::Any,
+    count: i32,
+    on_event: impl Fn(Event) + Send + 'static,
+) -> i32 {
+    for _ in 0..count {
+        on_event(Event::A);
+    }
+    count
+}
+
+#[test]
+fn unmock_with_impl_fn_parameter() {
+    let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let deps = Unimock::new(NotifierMock::notify.next_call(matching!(2, _)).unmocked());
+
+    let events_clone = events.clone();
+    assert_eq!(
+        2,
+        deps.notify(2, move |event| events_clone.lock().unwrap().push(event))
+    );
+    assert_eq!(vec![Event::A, Event::A], *events.lock().unwrap());
+}
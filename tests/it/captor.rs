@@ -0,0 +1,91 @@
+use unimock::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub name: String,
+}
+
+#[unimock(api=BusMock)]
+trait Bus {
+    fn publish(&self, event: Event);
+    fn log(&self, level: i32, message: &str);
+}
+
+fn code_under_test(bus: &impl Bus) {
+    bus.publish(Event {
+        name: "started".to_string(),
+    });
+    bus.log(1, "working");
+    bus.publish(Event {
+        name: "stopped".to_string(),
+    });
+}
+
+#[test]
+fn should_capture_owned_inputs_in_call_order() {
+    let events = Captor::new();
+    let messages = Captor::new();
+
+    let u = Unimock::new((
+        BusMock::publish
+            .each_call(matching!(_))
+            .capture(&events)
+            .returns(()),
+        BusMock::log
+            .each_call(matching!(_, _))
+            .capture_with(&messages, |(level, message)| format!("{level}: {message}"))
+            .returns(()),
+    ));
+
+    code_under_test(&u);
+
+    assert_eq!(
+        vec![
+            Event {
+                name: "started".to_string()
+            },
+            Event {
+                name: "stopped".to_string()
+            }
+        ],
+        events.values()
+    );
+    assert_eq!(Some("1: working".to_string()), messages.last());
+}
+
+#[test]
+fn should_only_capture_calls_matched_by_the_pattern() {
+    let captor = Captor::new();
+    let u = Unimock::new((
+        BusMock::log
+            .each_call(matching!(0, _))
+            .capture_with(&captor, |(_, message)| message.to_string())
+            .returns(()),
+        BusMock::log.each_call(matching!(_, _)).returns(()),
+    ));
+
+    u.log(1, "ignored");
+    u.log(0, "captured");
+
+    assert_eq!(vec!["captured".to_string()], captor.values());
+}
+
+#[test]
+fn should_capture_in_ordered_calls() {
+    let captor = Captor::new();
+    let u = Unimock::new((
+        BusMock::log
+            .next_call(matching!(_, _))
+            .capture_with(&captor, |(level, _)| *level)
+            .returns(()),
+        BusMock::log
+            .next_call(matching!(_, _))
+            .capture_with(&captor, |(level, _)| *level)
+            .returns(()),
+    ));
+
+    u.log(1, "a");
+    u.log(2, "b");
+
+    assert_eq!(vec![1, 2], captor.values());
+}
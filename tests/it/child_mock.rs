@@ -0,0 +1,101 @@
+use unimock::*;
+
+#[unimock(api=SessionMock)]
+pub trait Session {
+    fn query(&self, sql: &str) -> i32;
+}
+
+#[unimock(api=PoolMock)]
+pub trait Pool {
+    fn open_session(&self) -> Box<dyn Session>;
+}
+
+impl From<Unimock> for Box<dyn Session> {
+    fn from(unimock: Unimock) -> Self {
+        Box::new(unimock)
+    }
+}
+
+fn count_users(pool: &impl Pool) -> i32 {
+    pool.open_session().query("SELECT COUNT(*) FROM users")
+}
+
+#[test]
+fn child_mock_is_returned() {
+    let pool = Unimock::new(
+        PoolMock::open_session.next_call(matching!()).returns_mock(
+            SessionMock::query
+                .next_call(matching!("SELECT COUNT(*) FROM users"))
+                .returns(42),
+        ),
+    );
+
+    assert_eq!(42, count_users(&pool));
+}
+
+#[test]
+fn same_child_mock_is_returned_for_every_call() {
+    let pool = Unimock::new(PoolMock::open_session.each_call(matching!()).returns_mock(
+        SessionMock::query.stub(|each| {
+            each.call(matching!(_)).returns(1).n_times(2);
+        }),
+    ));
+
+    assert_eq!(1, count_users(&pool));
+    assert_eq!(1, count_users(&pool));
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool::open_session -> Session::query: Expected Session::query(\"SELECT 1\") at tests/it/child_mock.rs:56 to match exactly 1 call, but it actually matched no calls."
+)]
+fn child_mock_is_verified_with_parent() {
+    let pool = Unimock::new(
+        PoolMock::open_session.next_call(matching!()).returns_mock(
+            SessionMock::query
+                .next_call(matching!("SELECT 1"))
+                .returns(1),
+        ),
+    );
+
+    pool.open_session();
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool::open_session -> Session::query(sql: \"SELECT 2\"): No matching call patterns."
+)]
+fn child_mock_errors_are_reported_with_path() {
+    let pool = Unimock::new(
+        PoolMock::open_session.next_call(matching!()).returns_mock(
+            SessionMock::query
+                .each_call(matching!("SELECT 1"))
+                .returns(1),
+        ),
+    );
+
+    pool.open_session().query("SELECT 2");
+}
+
+#[unimock(api=ShardsMock)]
+pub trait Shards {
+    fn shard(&self, id: u32) -> Box<dyn Session>;
+}
+
+#[test]
+#[should_panic(
+    expected = "Shards::shard -> Session::query: Expected Session::query(\"SELECT 2\") at tests/it/child_mock.rs:95 to match exactly 1 call, but it actually matched no calls."
+)]
+fn child_mock_of_uncalled_pattern_is_verified() {
+    let shards = Unimock::new(ShardsMock::shard.stub(|each| {
+        each.call(matching!(1))
+            .returns_mock(SessionMock::query.each_call(matching!(_)).returns(1));
+        each.call(matching!(2)).returns_mock(
+            SessionMock::query
+                .next_call(matching!("SELECT 2"))
+                .returns(2),
+        );
+    }));
+
+    assert_eq!(1, shards.shard(1).query("SELECT 1"));
+}
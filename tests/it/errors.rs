@@ -6,7 +6,7 @@ trait SingleArg {
 }
 
 #[test]
-#[should_panic(expected = "SingleArg::method1(\"hoi\"): No mock implementation found.")]
+#[should_panic(expected = "SingleArg::method1(a: \"hoi\"): No mock implementation found.")]
 fn should_panic_for_nonexisting_mock() {
     Unimock::new(()).method1("hoi");
 }
@@ -43,7 +43,7 @@ fn should_panic_for_empty_stub_closure() {
 
 #[test]
 #[should_panic(
-    expected = "SingleArg::method1(\"whatever\"): No output available for after matching SingleArg::method1(_) at tests/it/errors.rs:50."
+    expected = "SingleArg::method1(a: \"whatever\"): No output available for after matching SingleArg::method1(_) at tests/it/errors.rs:50."
 )]
 fn call_pattern_without_output_factory_should_crash() {
     Unimock::new(SingleArgMock::method1.stub(|each| {
@@ -53,7 +53,7 @@ fn call_pattern_without_output_factory_should_crash() {
 }
 
 #[test]
-#[should_panic(expected = "SingleArg::method1(\"anything\"): No matching call patterns.")]
+#[should_panic(expected = "SingleArg::method1(a: \"anything\"): No matching call patterns.")]
 fn should_panic_if_no_call_patterns_in_stub_are_matched() {
     Unimock::new(SingleArgMock::method1.stub(|each| {
         each.call(matching!("something"));
@@ -75,7 +75,7 @@ fn call_pattern_with_count_expectation_should_panic_if_not_met() {
 
 #[test]
 #[should_panic(
-    expected = "SingleArg::method1(\"b\"): Explicit panic from SingleArg::method1(_) at tests/it/errors.rs:82: foobar!"
+    expected = "SingleArg::method1(a: \"b\"): Explicit panic from SingleArg::method1(_) at tests/it/errors.rs:82: foobar!"
 )]
 fn should_panic_with_explicit_message() {
     Unimock::new(SingleArgMock::method1.stub(|each| {
@@ -98,7 +98,7 @@ fn should_crash_when_the_original_instance_disappears_before_the_clone() {
 }
 
 #[test]
-#[should_panic(expected = "SingleArg::method1(\"\"): No mock implementation found.")]
+#[should_panic(expected = "SingleArg::method1(a: \"\"): No mock implementation found.")]
 fn multithread_error_reporting_works() {
     let unimock = Unimock::new(());
 
@@ -114,7 +114,7 @@ fn multithread_error_reporting_works() {
 
 #[test]
 #[should_panic(
-    expected = "Foo::foo(2): Cannot return value more than once from Foo::foo(_) at tests/it/errors.rs:125, because of missing Clone bound. Try using `.each_call()` or explicitly quantifying the response."
+    expected = "Foo::foo(arg: 2): Cannot return value more than once from Foo::foo(_) at tests/it/errors.rs:125, because of missing Clone bound. Try using `.each_call()` or explicitly quantifying the response."
 )]
 fn should_complain_when_returning_unquantified_value_more_then_once() {
     #[unimock(api=FooMock)]
@@ -159,9 +159,75 @@ fn should_crash_when_sending_original_unimock_to_another_thread() {
 
 #[test]
 #[should_panic(
-    expected = "SingleArg::method1(\"\"): No function supplied for matching inputs for call pattern SingleArg::method1[#0]."
+    expected = "SingleArg::method1(a: \"\"): No function supplied for matching inputs for call pattern SingleArg::method1[#0]."
 )]
 fn no_matcher_function() {
     let u = Unimock::new(SingleArgMock::method1.next_call(&|_| ()).returns(""));
     u.method1("");
 }
+
+#[test]
+#[should_panic(
+    expected = "SingleArg::method1(a: \"hoi\"): No mock implementation found.\nCalled at tests/it/errors.rs:174:22."
+)]
+fn should_report_call_site_of_failing_call() {
+    Unimock::new(()).method1("hoi");
+}
+
+#[unimock(api=AsyncTraitArgMock)]
+#[async_trait::async_trait]
+trait AsyncTraitArg {
+    async fn async_method(&self, a: &str) -> i32;
+}
+
+#[unimock(api=AsyncArgMock)]
+#[allow(async_fn_in_trait)]
+trait AsyncArg {
+    async fn native_async_method(&self, a: &str) -> i32;
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "AsyncTraitArg::async_method(a: \"hoi\"): No mock implementation found.\nCalled at tests/it/errors.rs:194:22."
+)]
+async fn should_report_call_site_of_failing_async_trait_call() {
+    Unimock::new(()).async_method("hoi").await;
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "AsyncArg::native_async_method(a: \"hoi\"): No mock implementation found.\nCalled at tests/it/errors.rs:202:5."
+)]
+async fn should_report_call_site_of_failing_async_fn_call() {
+    AsyncArg::native_async_method(&Unimock::new(()), "hoi").await;
+}
+
+#[test]
+#[should_panic(
+    expected = "SingleArg::method1(a: \"abcdefghijkl… 15 more bytes): No mock implementation found."
+)]
+fn should_truncate_rendered_inputs() {
+    Unimock::new(())
+        .with_max_input_len(16)
+        .method1("abcdefghijklmnopqrstuvwxyz");
+}
+
+#[test]
+fn should_truncate_values_in_mismatch_reports() {
+    let long = "x".repeat(100_000);
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            SingleArgMock::method1
+                .each_call(matching!("short"))
+                .returns("a"),
+        )
+        .with_max_input_len(64)
+        .with_max_diff_len(512);
+
+        u.method1(&long);
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains("more bytes"));
+    assert!(msg.len() < 2048, "message was {} bytes long", msg.len());
+}
@@ -59,7 +59,7 @@ mod param {
     #[test]
     #[should_panic(
         // Since the generic parameter has no Debug bound, we cannot see the parameter:
-        expected = "GenericParam::generic_param(?): No matching call patterns."
+        expected = "GenericParam::generic_param(param: ?): No matching call patterns."
     )]
     fn test_generic_param_panic_no_debug() {
         let deps = Unimock::new(
@@ -80,7 +80,7 @@ mod param {
     #[test]
     #[should_panic(
         // When it has a debug bound, we should see it:
-        expected = "GenericParamDebug::generic_param_debug(42): No matching call patterns."
+        expected = "GenericParamDebug::generic_param_debug(param: 42): No matching call patterns."
     )]
     fn test_generic_param_panic_debug() {
         let deps = Unimock::new(
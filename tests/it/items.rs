@@ -0,0 +1,96 @@
+use unimock::*;
+
+#[unimock(api=RepoMock)]
+trait Repo {
+    fn ids(&self) -> Box<dyn Iterator<Item = i32>>;
+    fn names(&self, prefix: &str) -> Box<dyn Iterator<Item = String> + Send>;
+}
+
+#[test]
+fn returns_items_for_every_call() {
+    let u = Unimock::new((
+        RepoMock::ids
+            .each_call(matching!())
+            .returns_items(vec![1, 2, 3]),
+        RepoMock::names
+            .next_call(matching!("a"))
+            .returns_items(["ab".to_string(), "ac".to_string()]),
+    ));
+
+    assert_eq!(vec![1, 2, 3], u.ids().collect::<Vec<_>>());
+    assert_eq!(Some(1), u.ids().next());
+    assert_eq!(
+        vec!["ab".to_string(), "ac".to_string()],
+        u.names("a").collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn consumed_items_are_verified() {
+    let u = Unimock::new(
+        RepoMock::ids
+            .next_call(matching!())
+            .returns_items(vec![1, 2, 3])
+            .consumed(2),
+    );
+
+    assert_eq!(vec![1, 2], u.ids().take(2).collect::<Vec<_>>());
+}
+
+#[test]
+fn consumed_items_combine_with_quantifiers() {
+    let u = Unimock::new((
+        RepoMock::ids
+            .each_call(matching!())
+            .returns_items(vec![1, 2, 3])
+            .consumed(4)
+            .n_times(2),
+        RepoMock::names
+            .each_call(matching!("a"))
+            .returns_items(["ab".to_string()])
+            .once(),
+    ));
+
+    assert_eq!(vec![1, 2, 3], u.ids().collect::<Vec<_>>());
+    assert_eq!(Some(1), u.ids().next());
+    assert_eq!(Some("ab".to_string()), u.names("a").next());
+}
+
+#[test]
+#[should_panic(
+    expected = "Repo::ids: Expected 2 items returned from Repo::ids() at tests/it/items.rs:66 to be consumed, but 3 were consumed."
+)]
+fn too_many_consumed_items() {
+    let u = Unimock::new(
+        RepoMock::ids
+            .next_call(matching!())
+            .returns_items(vec![1, 2, 3])
+            .consumed(2),
+    );
+
+    assert_eq!(3, u.ids().count());
+}
+
+#[cfg(feature = "futures")]
+mod stream {
+    use futures::stream::{BoxStream, StreamExt};
+    use unimock::*;
+
+    #[unimock(api=EventsMock)]
+    trait Events {
+        fn events(&self) -> BoxStream<'static, i32>;
+    }
+
+    #[tokio::test]
+    async fn returns_items_as_stream() {
+        let u = Unimock::new(
+            EventsMock::events
+                .each_call(matching!())
+                .returns_items(vec![1, 2, 3])
+                .consumed(4),
+        );
+
+        assert_eq!(vec![1, 2, 3], u.events().collect::<Vec<_>>().await);
+        assert_eq!(Some(1), u.events().next().await);
+    }
+}
@@ -1,13 +1,35 @@
+// Lints of newer toolchains which the tests predate
+#![allow(dead_code)]
+#![allow(unexpected_cfgs)]
+#![allow(clippy::multiple_bound_locations)]
+#![allow(clippy::useless_format)]
+
+mod args;
 mod basic;
+mod call_tree;
+mod callbacks;
+mod captor;
+mod child_mock;
 mod errors;
 mod generic;
+mod items;
+mod matchers;
 mod matching_eq;
+mod matching_named;
 mod matching_pat;
+#[cfg(feature = "regex")]
+mod matching_regex;
 mod mixed;
+mod mock_closure;
 mod mock_order;
+mod param_debug;
 mod prefix;
 #[cfg(feature = "pretty-print")]
 mod pretty_mismatches;
+mod report;
+mod returns_self;
+#[cfg(feature = "tracing")]
+mod tracing_events;
 mod unmock;
 
 fn main() {}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use unimock::matchers::*;
+use unimock::*;
+
+#[unimock(api=StoreMock)]
+trait Store {
+    fn put(&self, key: &str, value: f64) -> bool;
+    fn tags(&self, tags: Vec<String>) -> i32;
+    fn lookup(&self, table: HashMap<String, i32>) -> i32;
+}
+
+#[test]
+fn string_and_float_matchers() {
+    let u = Unimock::new((
+        StoreMock::put
+            .each_call(matching!(
+                is!(starts_with("user:")),
+                is!(approx(1.0, 0.001))
+            ))
+            .returns(true),
+        StoreMock::put
+            .each_call(matching!(is!(contains("tmp")), _))
+            .returns(false),
+    ));
+
+    assert!(u.put("user:1", 1.0005));
+    assert!(!u.put("/tmp/user:1", 1.0));
+}
+
+#[test]
+fn composed_collection_matchers() {
+    let u = Unimock::new((
+        StoreMock::tags
+            .each_call(matching!(is!(all_of((len(2), each(len(3)))))))
+            .returns(1),
+        StoreMock::tags
+            .each_call(matching!(is!(any_of((len(0), contains("x"))))))
+            .returns(2),
+        StoreMock::tags
+            .each_call(matching!(is!(not(contains("a")))))
+            .returns(3),
+    ));
+
+    assert_eq!(1, u.tags(vec!["foo".to_string(), "bar".to_string()]));
+    assert_eq!(2, u.tags(vec![]));
+    assert_eq!(2, u.tags(vec!["x".to_string()]));
+    assert_eq!(3, u.tags(vec!["b".to_string()]));
+}
+
+#[test]
+fn map_contains_key() {
+    let u = Unimock::new(
+        StoreMock::lookup
+            .next_call(matching!(is!(contains("users"))))
+            .returns(42),
+    );
+
+    assert_eq!(42, u.lookup(HashMap::from([("users".to_string(), 1)])));
+}
+
+#[test]
+#[should_panic(
+    expected = "Store::tags(tags: [\"foo\"]): No matching call patterns. \nMatcher mismatch for input `tags`:\nActual value did not satisfy `all_of(len(2), each(len(3)))`:\n[\"foo\"]\n"
+)]
+fn should_describe_matcher_mismatch() {
+    let u = Unimock::new(
+        StoreMock::tags
+            .each_call(matching!(is!(all_of((len(2), each(len(3)))))))
+            .returns(1),
+    );
+
+    u.tags(vec!["foo".to_string()]);
+}
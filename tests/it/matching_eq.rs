@@ -50,3 +50,67 @@ fn eq_or() {
     assert_eq!(42, <Unimock as Test>::f(&u, 0, Data(vec![1337])));
     assert_eq!(0, <Unimock as Test>::f(&u, 0, Data(vec![123])));
 }
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Order {
+    pub id: u32,
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Item {
+    pub name: &'static str,
+    pub price: u32,
+}
+
+#[unimock(api=OrdersMock)]
+trait Orders {
+    fn place(&self, order: Order);
+}
+
+fn order(prices: [u32; 3]) -> Order {
+    Order {
+        id: 7,
+        items: prices
+            .into_iter()
+            .map(|price| Item {
+                name: "apple",
+                price,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "Equality mismatch for input `order` (actual / expected):\nitems[2].price: 10 != 12\n"
+)]
+fn structural_diff_should_report_differing_field_paths() {
+    let u = Unimock::new(
+        OrdersMock::place
+            .each_call(matching!(eq!(&order([1, 2, 12]))))
+            .returns(()),
+    )
+    .with_diff_mode(DiffMode::Structural);
+
+    u.place(order([1, 2, 10]));
+}
+
+#[test]
+fn structural_diff_should_diff_truncated_values() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            OrdersMock::place
+                .each_call(matching!(eq!(&order([1, 2, 12]))))
+                .returns(()),
+        )
+        .with_diff_mode(DiffMode::Structural)
+        .with_max_input_len(64);
+
+        u.place(order([1, 2, 10]));
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains("more bytes"));
+    assert!(!msg.contains("items[2].price"));
+}
@@ -0,0 +1,149 @@
+use unimock::*;
+
+#[unimock(api=UserServiceMock)]
+trait UserService {
+    fn update(&self, tenant: &str, user_id: u32, name: String, active: bool) -> bool;
+    fn count(&self, tenant: &str) -> i32;
+}
+
+#[test]
+fn should_match_named_inputs() {
+    let u = Unimock::new((
+        UserServiceMock::update
+            .each_call(matching!(user_id: 42, active: true))
+            .returns(true),
+        UserServiceMock::update
+            .each_call(matching!(name: eq!("root"), tenant: "admin" | "ops"))
+            .returns(true),
+        UserServiceMock::update
+            .each_call(matching!(..))
+            .returns(false),
+    ));
+
+    assert!(u.update("acme", 42, "alice".to_string(), true));
+    assert!(!u.update("acme", 42, "alice".to_string(), false));
+    assert!(u.update("ops", 1, "root".to_string(), false));
+    assert!(!u.update("acme", 1, "root".to_string(), false));
+}
+
+#[test]
+fn should_match_positional_inputs_with_rest() {
+    let u = Unimock::new((
+        UserServiceMock::update
+            .each_call(matching!("acme", 42, ..))
+            .returns(true),
+        UserServiceMock::update
+            .each_call(matching!(("ops", ..) | ("admin", ..)))
+            .returns(true),
+        UserServiceMock::update
+            .each_call(matching!(_, ..))
+            .returns(false),
+    ));
+
+    assert!(u.update("acme", 42, "alice".to_string(), false));
+    assert!(u.update("admin", 1, "bob".to_string(), false));
+    assert!(!u.update("acme", 1, "alice".to_string(), true));
+}
+
+#[test]
+fn should_match_single_positional_input_with_rest() {
+    let u = Unimock::new((
+        UserServiceMock::count
+            .next_call(matching!("acme", ..))
+            .returns(1),
+        UserServiceMock::count
+            .next_call(matching!("ops" | "admin", ..))
+            .returns(2),
+    ));
+
+    assert_eq!(1, u.count("acme"));
+    assert_eq!(2, u.count("ops"));
+}
+
+#[unimock(api=GenericCountMock)]
+trait GenericCount<T: std::fmt::Debug + PartialEq<i32>> {
+    fn generic_count(&self, value: T) -> i32;
+}
+
+#[test]
+fn should_match_single_generic_input_with_rest() {
+    let u = Unimock::new(
+        GenericCountMock::generic_count
+            .with_types::<i32>()
+            .next_call(matching!(1, ..))
+            .returns(1),
+    );
+
+    assert_eq!(1, u.generic_count(1));
+}
+
+#[test]
+#[should_panic(
+    expected = "UserService::count(tenant: \"ops\"): No matching call patterns. \nPattern mismatch for input `tenant` (actual / expected):"
+)]
+fn should_report_single_positional_input_with_rest() {
+    let u = Unimock::new(
+        UserServiceMock::count
+            .each_call(matching!("acme", ..))
+            .returns(1),
+    );
+
+    u.count("ops");
+}
+
+#[test]
+fn should_match_named_inputs_with_guard() {
+    let u = Unimock::new(
+        UserServiceMock::update
+            .each_call(matching!(user_id: id, name: name, .. if *id > 10 && name.len() < 5))
+            .returns(true),
+    );
+
+    assert!(u.update("acme", 11, "bob".to_string(), true));
+}
+
+#[test]
+fn should_match_single_named_input() {
+    let u = Unimock::new(
+        UserServiceMock::count
+            .each_call(matching!(tenant: "acme"))
+            .returns(3),
+    );
+
+    assert_eq!(3, u.count("acme"));
+}
+
+#[test]
+#[should_panic(
+    expected = "UserService::update(tenant: \"acme\", user_id: 7, name: \"alice\", active: true): No matching call patterns. \nPattern mismatch for input `user_id` (actual / expected):"
+)]
+fn should_report_mismatch_by_input_name() {
+    let u = Unimock::new(
+        UserServiceMock::update
+            .each_call(matching!(user_id: 42, ..))
+            .returns(true),
+    );
+
+    u.update("acme", 7, "alice".to_string(), true);
+}
+
+#[test]
+#[should_panic(expected = "Equality mismatch for input `name` (actual / expected):")]
+fn should_report_eq_mismatch_by_input_name() {
+    let u = Unimock::new(
+        UserServiceMock::update
+            .each_call(matching!(user_id: 7, name: eq!("bob")))
+            .returns(true),
+    );
+
+    u.update("acme", 7, "alice".to_string(), true);
+}
+
+#[test]
+fn should_expose_input_names() {
+    assert_eq!(
+        &["tenant", "user_id", "name", "active"],
+        <UserServiceMock::update as MockFn>::INPUT_NAMES
+    );
+    assert_eq!(&["tenant"], <UserServiceMock::count as MockFn>::INPUT_NAMES);
+}
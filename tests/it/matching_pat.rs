@@ -41,3 +41,36 @@ fn matching_str_or() {
     );
     u.f("a".to_string());
 }
+
+#[unimock(api=GuardedMock)]
+trait Guarded {
+    fn check(&self, name: &str, min_len: usize, tag: Option<i32>);
+}
+
+#[test]
+#[should_panic(
+    expected = "Guarded::check(name: \"ab\", min_len: 3, tag: Some(1)): No matching call patterns. \nGuard mismatch:\nAll inputs matched the pattern, but the guard `a.len() > *b` evaluated to false.\n"
+)]
+fn should_report_failing_guard() {
+    let u = Unimock::new(
+        GuardedMock::check
+            .each_call(matching!((a, b, Some(_)) if a.len() > *b))
+            .returns(()),
+    );
+
+    u.check("ab", 3, Some(1));
+}
+
+#[test]
+#[should_panic(
+    expected = "Guarded::check(name: \"abcd\", min_len: 3, tag: None): No matching call patterns. \nPattern mismatch for input `tag` (actual / expected):"
+)]
+fn should_report_pattern_mismatch_when_guard_is_present() {
+    let u = Unimock::new(
+        GuardedMock::check
+            .each_call(matching!((a, b, Some(_)) if a.len() > *b))
+            .returns(()),
+    );
+
+    u.check("abcd", 3, None);
+}
@@ -0,0 +1,59 @@
+use unimock::*;
+
+#[unimock(api=DbMock)]
+trait Db {
+    fn query(&self, sql: &str) -> i32;
+    fn fetch(&self, method: &str, url: String) -> i32;
+}
+
+#[test]
+fn should_match_regex() {
+    let u = Unimock::new((
+        DbMock::query
+            .each_call(matching!(regex!("^SELECT .* FROM users")))
+            .returns(1),
+        DbMock::query.each_call(matching!(_)).returns(2),
+    ));
+
+    assert_eq!(1, u.query("SELECT name FROM users WHERE id = 1"));
+    assert_eq!(2, u.query("SELECT name FROM groups"));
+}
+
+#[test]
+fn should_match_regex_together_with_other_patterns() {
+    let u = Unimock::new(
+        DbMock::fetch
+            .each_call(matching!(
+                "GET" | "HEAD",
+                regex!(r"^https://example\.com/\d+$")
+            ))
+            .returns(200),
+    );
+
+    assert_eq!(200, u.fetch("GET", "https://example.com/42".to_string()));
+    assert_eq!(200, u.fetch("HEAD", "https://example.com/7".to_string()));
+}
+
+#[test]
+#[should_panic(
+    expected = "Db::query(sql: \"DELETE FROM users\"): No matching call patterns. \nRegex mismatch for input `sql` (actual / expected):"
+)]
+fn should_report_regex_mismatch() {
+    let u = Unimock::new(
+        DbMock::query
+            .each_call(matching!(regex!("^SELECT .* FROM users")))
+            .returns(1),
+    );
+
+    u.query("DELETE FROM users");
+}
+
+#[test]
+#[should_panic(expected = "Invalid regex in matching!")]
+fn should_panic_on_invalid_regex() {
+    let _ = Unimock::new(
+        DbMock::query
+            .each_call(matching!(regex!("(unclosed")))
+            .returns(1),
+    );
+}
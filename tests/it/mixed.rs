@@ -187,3 +187,78 @@ fn mixed_tuple_clone_combinatorics_many() {
         );
     }
 }
+
+#[unimock(api = NestedMock)]
+trait Nested {
+    fn cow(&self) -> std::borrow::Cow<'_, str>;
+    fn boxed(&self) -> Box<dyn Fn(i32) -> i32 + Send + Sync>;
+    fn result_vec(&self) -> Result<Vec<&String>, i32>;
+    fn option_option(&self) -> Option<Option<&String>>;
+    fn hash_map(&self) -> std::collections::HashMap<i32, &String>;
+    fn tuple_cow(&self) -> (std::borrow::Cow<'_, str>, &i32);
+}
+
+#[test]
+fn nested_cow_and_box() {
+    let u = Unimock::new((
+        NestedMock::cow
+            .each_call(matching!())
+            .returns(std::borrow::Cow::Borrowed("cow")),
+        NestedMock::boxed
+            .next_call(matching!())
+            .answers(|_| -> Box<dyn Fn(i32) -> i32 + Send + Sync> { Box::new(|arg| arg * 2) }),
+    ));
+
+    assert_eq!("cow", u.cow());
+    assert_eq!("cow", u.cow());
+    assert_eq!(42, u.boxed()(21));
+}
+
+#[test]
+fn nested_result_vec() {
+    let u = Unimock::new((
+        NestedMock::result_vec
+            .next_call(matching!())
+            .returns(Ok(vec!["a".to_string(), "b".to_string()])),
+        NestedMock::result_vec
+            .next_call(matching!())
+            .returns(Err::<Vec<String>, _>(42)),
+    ));
+
+    assert_eq!(Ok(vec![&"a".to_string(), &"b".to_string()]), u.result_vec());
+    assert_eq!(Err(42), u.result_vec());
+}
+
+#[test]
+fn nested_option_option() {
+    let u = Unimock::new(
+        NestedMock::option_option
+            .each_call(matching!())
+            .returns(Some(Some("a".to_string()))),
+    );
+
+    assert_eq!(Some(Some(&"a".to_string())), u.option_option());
+    assert_eq!(Some(Some(&"a".to_string())), u.option_option());
+}
+
+#[test]
+fn nested_hash_map() {
+    let u = Unimock::new(
+        NestedMock::hash_map
+            .next_call(matching!())
+            .returns(std::collections::HashMap::from([(1, "a".to_string())])),
+    );
+
+    assert_eq!(Some(&&"a".to_string()), u.hash_map().get(&1));
+}
+
+#[test]
+fn nested_tuple_cow() {
+    let u = Unimock::new(
+        NestedMock::tuple_cow
+            .next_call(matching!())
+            .returns((std::borrow::Cow::Owned::<str>("owned".to_string()), 42)),
+    );
+
+    assert_eq!((std::borrow::Cow::Borrowed("owned"), &42), u.tuple_cow());
+}
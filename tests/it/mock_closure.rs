@@ -0,0 +1,99 @@
+use unimock::*;
+
+#[unimock(api=HandlerMock)]
+trait Handler {
+    fn handle(&self, request: i32) -> String;
+}
+
+#[unimock(api=CombineMock)]
+trait Combine {
+    fn combine(&self, a: &str, b: &str) -> String;
+}
+
+fn serve(requests: &[i32], handler: impl Fn(i32) -> String) -> Vec<String> {
+    requests.iter().map(|request| handler(*request)).collect()
+}
+
+#[test]
+fn mock_closure_can_be_passed_as_impl_fn() {
+    let handler = MockClosure::<HandlerMock::handle>::new(
+        HandlerMock::handle
+            .each_call(matching!(_))
+            .answers(|request| format!("response {request}"))
+            .n_times(2),
+    );
+
+    assert_eq!(
+        vec!["response 1".to_string(), "response 2".to_string()],
+        serve(&[1, 2], handler.into_fn())
+    );
+}
+
+#[test]
+fn mock_closure_with_multiple_inputs() {
+    let combine = MockClosure::<CombineMock::combine>::new(
+        CombineMock::combine
+            .next_call(matching!("a", "b"))
+            .returns("ab"),
+    );
+
+    let f = |a: &str, b: &str| combine.call((a, b));
+    assert_eq!("ab", f("a", "b"));
+}
+
+#[test]
+fn cloned_mock_closure_shares_state() {
+    let handler = MockClosure::<HandlerMock::handle>::new(
+        HandlerMock::handle
+            .each_call(matching!(_))
+            .returns("ok")
+            .n_times(2),
+    );
+
+    let cloned = handler.clone();
+    assert_eq!("ok", handler.call(1));
+    assert_eq!("ok", cloned.call(2));
+}
+
+#[test]
+#[should_panic(expected = "Handler::handle(request: 42): No matching call patterns.")]
+fn mock_closure_panics_on_unmatched_call() {
+    let handler = MockClosure::<HandlerMock::handle>::new(
+        HandlerMock::handle.some_call(matching!(1)).returns("one"),
+    );
+
+    serve(&[42], handler.into_fn());
+}
+
+#[test]
+#[should_panic(
+    expected = "Mock for Handler::handle was never called. Dead mocks should be removed."
+)]
+fn mock_closure_is_verified_on_drop() {
+    MockClosure::<HandlerMock::handle>::new(
+        HandlerMock::handle
+            .some_call(matching!(_))
+            .returns("unused"),
+    );
+}
+
+#[unimock(api=AddMock)]
+trait Add {
+    fn add(&self, a: i32, b: i32) -> i32;
+}
+
+fn fold(values: &[i32], add: impl Fn(i32, i32) -> i32) -> i32 {
+    values.iter().fold(0, |acc, value| add(acc, *value))
+}
+
+#[test]
+fn mock_closure_can_be_passed_as_multi_parameter_impl_fn() {
+    let add = MockClosure::<AddMock::add>::new(
+        AddMock::add
+            .each_call(matching!(_, _))
+            .answers(|(a, b)| a + b)
+            .n_times(3),
+    );
+
+    assert_eq!(6, fold(&[1, 2, 3], add.into_fn2()));
+}
@@ -13,7 +13,7 @@ trait T2 {
 
 #[test]
 #[should_panic(
-    expected = "T2::c(2): Method matched in wrong order. Expected a call matching T1::a(2) at tests/it/mock_order.rs:25."
+    expected = "T2::c(i: 2): Method matched in wrong order. Expected a call matching T1::a(2) at tests/it/mock_order.rs:25."
 )]
 fn two_fns_in_incorrect_order_should_fail_and_presence_of_a_stub_should_not_influence_order() {
     let m = Unimock::new((
@@ -35,7 +35,7 @@ fn two_fns_in_incorrect_order_should_fail_and_presence_of_a_stub_should_not_infl
 
 #[test]
 #[should_panic(
-    expected = "T1::a(2): Ordered call (3) out of range: There were no more ordered call patterns in line for selection."
+    expected = "T1::a(i: 2): Ordered call (3) out of range: There were no more ordered call patterns in line for selection."
 )]
 fn should_give_sensible_panic_message_when_calling_beyond_mocked_order() {
     let m = Unimock::new((
@@ -50,7 +50,7 @@ fn should_give_sensible_panic_message_when_calling_beyond_mocked_order() {
 
 #[test]
 #[should_panic(
-    expected = "T1::a(0): Method invoked in the correct order (3), but inputs didn't match T1::a(1) at tests/it/mock_order.rs:58."
+    expected = "T1::a(i: 0): Method invoked in the correct order (3), but inputs didn't match T1::a(1) at tests/it/mock_order.rs:58."
 )]
 fn calling_expired_pattern_should_fail() {
     let m = Unimock::new((
@@ -65,7 +65,7 @@ fn calling_expired_pattern_should_fail() {
 
 #[test]
 #[should_panic(
-    expected = "T1::a(0): Method invoked in the correct order (1), but inputs didn't match T1::a(1 | 2) | (3 | 4) at tests/it/mock_order.rs:71."
+    expected = "T1::a(i: 0): Method invoked in the correct order (1), but inputs didn't match T1::a(1 | 2) | (3 | 4) at tests/it/mock_order.rs:71."
 )]
 fn call_order_error_with_complex_pattern() {
     let m = Unimock::new(T1Mock::a.next_call(matching!((1 | 2) | (3 | 4))).returns(0));
@@ -74,7 +74,7 @@ fn call_order_error_with_complex_pattern() {
 
 #[test]
 #[should_panic(
-    expected = "T1::a(0): Method invoked in the correct order (1), but inputs didn't match T1::a(x) if {guard} at tests/it/mock_order.rs:80."
+    expected = "T1::a(i: 0): Method invoked in the correct order (1), but inputs didn't match T1::a(x) if {guard} at tests/it/mock_order.rs:80."
 )]
 fn call_order_error_with_guard_pattern() {
     let m = Unimock::new(T1Mock::a.next_call(matching!((x) if x * 2 == 7)).returns(0));
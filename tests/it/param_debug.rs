@@ -0,0 +1,129 @@
+use unimock::*;
+
+#[derive(PartialEq)]
+pub struct Token(String);
+
+fn token_prefix(token: &Token) -> String {
+    format!("Token({}..)", &token.0[..3])
+}
+
+fn short_id(id: &u64) -> String {
+    format!("#{}", id % 1000)
+}
+
+#[unimock(api=AuthMock)]
+trait Auth {
+    fn login(&self, user: &str, #[unimock(redact)] password: &str) -> bool;
+    fn verify(
+        &self,
+        #[unimock(debug_with = token_prefix)] token: Token,
+        #[unimock(debug_with = short_id)] id: u64,
+    ) -> bool;
+}
+
+#[test]
+#[should_panic(
+    expected = "Auth::login(user: \"alice\", password: <redacted>): No mock implementation found."
+)]
+fn should_redact_input_in_error_message() {
+    Unimock::new(()).login("alice", "hunter2");
+}
+
+#[test]
+#[should_panic(
+    expected = "Auth::verify(token: Token(abc..), id: #345): No mock implementation found."
+)]
+fn should_render_input_with_custom_debug_function() {
+    Unimock::new(()).verify(Token("abcdef".to_string()), 12345);
+}
+
+#[test]
+fn should_redact_input_in_mismatch_report() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            AuthMock::login
+                .each_call(matching!(_, "secret"))
+                .returns(true),
+        );
+
+        u.login("alice", "hunter2");
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains("Pattern mismatch for input `password`:"));
+    assert!(msg.contains("redacted"));
+    assert!(!msg.contains("hunter2"));
+    assert!(!msg.contains("secret"));
+}
+
+#[test]
+fn should_not_show_expected_value_of_redacted_input_in_eq_mismatch() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            AuthMock::login
+                .each_call(matching!(_, eq!(&String::from("real-prod-token"))))
+                .returns(true),
+        );
+
+        u.login("alice", "hunter2");
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains(
+        "Equality mismatch for input `password`:\nThe input is redacted, so its actual and expected values are not shown."
+    ));
+    assert!(!msg.contains("hunter2"));
+    assert!(!msg.contains("real-prod-token"));
+}
+
+#[test]
+fn should_render_expected_value_with_custom_debug_function_in_eq_mismatch() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            AuthMock::verify
+                .each_call(matching!(eq!(&Token("xyzSECRET2".to_string())), _))
+                .returns(true),
+        );
+
+        u.verify(Token("abcSECRET1".to_string()), 12345);
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains("Equality mismatch for input `token` (actual / expected):"));
+    assert!(msg.contains("abc"));
+    assert!(msg.contains("xyz"));
+    assert!(!msg.contains("SECRET1"));
+    assert!(!msg.contains("SECRET2"));
+}
+
+#[test]
+fn should_not_show_expected_value_of_input_with_custom_debug_function_in_pattern_mismatch() {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            AuthMock::verify
+                .each_call(matching!(_, 42000))
+                .returns(true),
+        );
+
+        u.verify(Token("abcSECRET1".to_string()), 12345);
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+
+    assert!(msg.contains(
+        "Pattern mismatch for input `id`:\nThe input has a custom debug representation, so its expected value is not shown. Actual value:\n#345\n"
+    ));
+    assert!(!msg.contains("42000"));
+}
+
+#[test]
+fn should_expose_custom_debug_input() {
+    let inputs = ("alice", "hunter2");
+    assert_eq!(
+        None,
+        <AuthMock::login as MockFn>::custom_debug_input(&inputs, 0)
+    );
+    assert_eq!(
+        Some("<redacted>".to_string()),
+        <AuthMock::login as MockFn>::custom_debug_input(&inputs, 1)
+    );
+}
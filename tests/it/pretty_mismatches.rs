@@ -2,7 +2,7 @@ use unimock::*;
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo([\"a\"]): Method invoked in the correct order (1), but inputs didn't match Trait::foo([]) at tests/it/pretty_mismatches.rs:13. \nPattern mismatch for input #0 (actual / expected):\n\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m< left\u{1b}[0m / \u{1b}[32mright >\u{1b}[0m :\n\u{1b}[31m<[\u{1b}[0m\u{1b}[1;48;5;52;31m\"a\"\u{1b}[0m\u{1b}[31m]\u{1b}[0m\n\u{1b}[32m>[]\u{1b}[0m\n"
+    expected = "Trait::foo(arg: [\"a\"]): Method invoked in the correct order (1), but inputs didn't match Trait::foo([]) at tests/it/pretty_mismatches.rs:13. \nPattern mismatch for input `arg` (actual / expected):\n\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m< left\u{1b}[0m / \u{1b}[32mright >\u{1b}[0m :\n\u{1b}[31m<[\u{1b}[0m\u{1b}[1;48;5;52;31m\"a\"\u{1b}[0m\u{1b}[31m]\u{1b}[0m\n\u{1b}[32m>[]\u{1b}[0m\n"
 )]
 fn should_print_pattern_mismatch_on_call_order_failure() {
     #[unimock(api=TraitMock)]
@@ -16,7 +16,7 @@ fn should_print_pattern_mismatch_on_call_order_failure() {
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo(S { value: \"b\" }): Method invoked in the correct order (1), but inputs didn't match Trait::foo(eq!(..)) at tests/it/pretty_mismatches.rs:34. \nEquality mismatch for input #0 (actual / expected):\n\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m< left\u{1b}[0m / \u{1b}[32mright >\u{1b}[0m :\n\u{1b}[31m<S { value: \"\u{1b}[0m\u{1b}[1;48;5;52;31mb\u{1b}[0m\u{1b}[31m\" }\u{1b}[0m\n\u{1b}[32m>S { value: \"\u{1b}[0m\u{1b}[1;48;5;22;32ma\u{1b}[0m\u{1b}[32m\" }\u{1b}[0m\n"
+    expected = "Trait::foo(s: S { value: \"b\" }): Method invoked in the correct order (1), but inputs didn't match Trait::foo(eq!(..)) at tests/it/pretty_mismatches.rs:34. \nEquality mismatch for input `s` (actual / expected):\n\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m< left\u{1b}[0m / \u{1b}[32mright >\u{1b}[0m :\n\u{1b}[31m<S { value: \"\u{1b}[0m\u{1b}[1;48;5;52;31mb\u{1b}[0m\u{1b}[31m\" }\u{1b}[0m\n\u{1b}[32m>S { value: \"\u{1b}[0m\u{1b}[1;48;5;22;32ma\u{1b}[0m\u{1b}[32m\" }\u{1b}[0m\n"
 )]
 fn should_print_eq_mismatch_on_call_order_failure() {
     #[derive(Debug, Eq, PartialEq)]
@@ -44,7 +44,7 @@ fn should_print_eq_mismatch_on_call_order_failure() {
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo(?): Method invoked in the correct order (1), but inputs didn't match Trait::foo(eq!(..)) at tests/it/pretty_mismatches.rs:62. \nEquality mismatch for input #0:\nActual value did not equal expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
+    expected = "Trait::foo(s: ?): Method invoked in the correct order (1), but inputs didn't match Trait::foo(eq!(..)) at tests/it/pretty_mismatches.rs:62. \nEquality mismatch for input `s`:\nActual value did not equal expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
 )]
 fn should_print_message_about_missing_debug() {
     #[derive(Eq, PartialEq)]
@@ -72,7 +72,7 @@ fn should_print_message_about_missing_debug() {
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo(S { value: \"a\" }): Method invoked in the correct order (1), but inputs didn't match Trait::foo(ne!(..)) at tests/it/pretty_mismatches.rs:90. \nInequality mismatch for input #0:\nS { value: \"a\" }"
+    expected = "Trait::foo(s: S { value: \"a\" }): Method invoked in the correct order (1), but inputs didn't match Trait::foo(ne!(..)) at tests/it/pretty_mismatches.rs:90. \nInequality mismatch for input `s`:\nS { value: \"a\" }"
 )]
 fn should_print_message_about_failed_inequality_check() {
     #[derive(Debug, Eq, PartialEq)]
@@ -100,7 +100,7 @@ fn should_print_message_about_failed_inequality_check() {
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo(?): Method invoked in the correct order (1), but inputs didn't match Trait::foo(ne!(..)) at tests/it/pretty_mismatches.rs:118. \nInequality mismatch for input #0:\nActual value unexpectedly equalled expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
+    expected = "Trait::foo(s: ?): Method invoked in the correct order (1), but inputs didn't match Trait::foo(ne!(..)) at tests/it/pretty_mismatches.rs:118. \nInequality mismatch for input `s`:\nActual value unexpectedly equalled expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
 )]
 fn should_complain_about_missing_debug_representation_for_inequality_mismatch() {
     #[derive(Eq, PartialEq)]
@@ -128,7 +128,7 @@ fn should_complain_about_missing_debug_representation_for_inequality_mismatch()
 
 #[test]
 #[should_panic(
-    expected = "Trait::foo(?): No matching call patterns. \nPattern mismatch for call pattern #0, input #0:\nActual value did not match expected pattern, but can't display diagnostics because the type is likely missing #[derive(Debug)].\nEquality mismatch for call pattern #1, input #0:\nActual value did not equal expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
+    expected = "Trait::foo(s: ?): No matching call patterns. Closest pattern: #0 Trait::foo(S(\"a\")) at tests/it/pretty_mismatches.rs:143 (1 of 1 inputs differ).\nPattern mismatch for call pattern #0, input `s`:\nActual value did not match expected pattern, but can't display diagnostics because the type is likely missing #[derive(Debug)].\nEquality mismatch for call pattern #1, input `s`:\nActual value did not equal expected value, but can't display diagnostics because the type is likely missing #[derive(Debug)]."
 )]
 fn should_print_all_mismatches_on_matched_function() {
     #[derive(Eq, PartialEq)]
@@ -144,7 +144,142 @@ fn should_print_all_mismatches_on_matched_function() {
         TraitMock::foo
             .some_call(matching!(eq!(&S("b"))))
             .returns(()),
-    ));
+    ))
+    .with_verbosity(Verbosity::Verbose);
 
     u.foo(S("c"));
 }
+
+#[unimock(api=RouterMock)]
+trait Router {
+    fn route(&self, method: &str, path: &str, port: u16) -> i32;
+}
+
+#[test]
+#[should_panic(
+    expected = "Router::route(method: \"GET\", path: \"/b\", port: 80): No matching call patterns. Closest pattern: #1 Router::route(\"GET\", \"/a\", 80) at tests/it/pretty_mismatches.rs:"
+)]
+fn should_suggest_closest_pattern() {
+    let u = Unimock::new((
+        RouterMock::route
+            .each_call(matching!("POST", "/a", 443))
+            .returns(0),
+        RouterMock::route
+            .each_call(matching!("GET", "/a", 80))
+            .returns(1),
+        RouterMock::route
+            .each_call(matching!("PUT", "/b", 8080))
+            .returns(2),
+    ));
+
+    u.route("GET", "/b", 80);
+}
+
+#[test]
+#[should_panic(
+    expected = "(1 of 3 inputs differ). Mismatches of 2 other call patterns omitted, use `Verbosity::Verbose` to report all.\nPattern mismatch for input `path` (actual / expected):\n"
+)]
+fn should_only_report_mismatches_of_closest_pattern() {
+    let u = Unimock::new((
+        RouterMock::route
+            .each_call(matching!("POST", "/a", 443))
+            .returns(0),
+        RouterMock::route
+            .each_call(matching!("GET", "/a", 80))
+            .returns(1),
+        RouterMock::route
+            .each_call(matching!("PUT", "/b", 8080))
+            .returns(2),
+    ));
+
+    u.route("GET", "/b", 80);
+}
+
+#[test]
+#[should_panic(expected = "(all inputs matched, but the guard failed).")]
+fn should_rank_failed_guard_as_closest_pattern() {
+    let u = Unimock::new((
+        RouterMock::route
+            .each_call(matching!("POST", _, _))
+            .returns(0),
+        RouterMock::route
+            .each_call(matching!(("GET", _, port) if *port > 1000))
+            .returns(1),
+    ));
+
+    u.route("GET", "/b", 80);
+}
+
+#[test]
+#[should_panic(
+    expected = "(1 of 3 inputs differ). Mismatches of 1 other call pattern omitted, use `Verbosity::Verbose` to report all.\nPattern mismatch for call pattern #0, input `method` (actual / expected):\n"
+)]
+fn should_report_mismatches_of_patterns_tied_with_closest_pattern() {
+    let u = Unimock::new((
+        RouterMock::route
+            .each_call(matching!("POST", "/b", 80))
+            .returns(0),
+        RouterMock::route
+            .each_call(matching!("GET", "/a", 80))
+            .returns(1),
+        RouterMock::route
+            .each_call(matching!("PUT", "/c", 8080))
+            .returns(2),
+    ));
+
+    u.route("GET", "/b", 80);
+}
+
+#[test]
+#[should_panic(
+    expected = "(1 of 3 inputs differ).\nPattern mismatch for input `path` (actual / expected):\n"
+)]
+fn should_not_count_patterns_without_mismatches_as_omitted() {
+    let u = Unimock::new((
+        RouterMock::route
+            .each_call(&|m| m.func(|_| false))
+            .returns(0),
+        RouterMock::route
+            .each_call(matching!("GET", "/a", 80))
+            .returns(1),
+    ));
+
+    u.route("GET", "/b", 80);
+}
+
+#[unimock(api=TruncatedMock)]
+trait Truncated {
+    fn truncated(&self, arg: &str);
+}
+
+fn truncated_diff(max_diff_len: usize) -> String {
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(
+            TruncatedMock::truncated
+                .each_call(matching!("expected"))
+                .returns(()),
+        )
+        .with_max_diff_len(max_diff_len);
+
+        u.truncated("actual");
+    });
+    let msg = result.unwrap_err().downcast::<String>().unwrap();
+    let diff_start = msg.find("(actual / expected):\n").unwrap() + 21;
+    msg[diff_start..msg.find("\nCalled at").unwrap()].to_string()
+}
+
+#[test]
+fn should_reset_styling_of_diff_truncated_within_a_line() {
+    assert_eq!(
+        "\u{1b}[1mDiff\u{1b}[0m \u{1b}[0m… 189 more bytes",
+        truncated_diff(16)
+    );
+}
+
+#[test]
+fn should_truncate_diff_at_the_end_of_a_line() {
+    assert_eq!(
+        "\u{1b}[1mDiff\u{1b}[0m \u{1b}[31m< left\u{1b}[0m / \u{1b}[32mright >\u{1b}[0m :\n… 152 more bytes",
+        truncated_diff(60)
+    );
+}
@@ -0,0 +1,92 @@
+use unimock::*;
+
+#[unimock(api=KvMock)]
+trait Kv {
+    fn get(&self, key: &str) -> Option<i32>;
+}
+
+fn report_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "unimock-report-{name}-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn read_lines(path: &std::path::Path) -> Vec<String> {
+    let contents = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    contents.lines().map(str::to_string).collect()
+}
+
+#[test]
+fn should_report_mock_error_as_json_line() {
+    let path = report_path("mock-error");
+
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new(KvMock::get.each_call(matching!("a")).returns(Some(1)))
+            .with_report(ReportTarget::File(path.clone()));
+
+        u.get("b");
+    });
+    assert!(result.is_err());
+
+    let lines = read_lines(&path);
+    assert_eq!(1, lines.len());
+    let line = &lines[0];
+    assert!(line.starts_with(
+        r#"{"error":"no_matching_call_patterns","function":"Kv::get","inputs":"(key: \"b\")","call_site":"tests/it/report.rs:"#
+    ));
+    assert!(line.contains(
+        r#""mismatches":[{"pattern_index":0,"pattern_location":"tests/it/report.rs:28","input_index":0,"input_name":"key","kind":"pattern","actual":"\"b\"","expected":"\"a\""}]"#
+    ));
+    assert!(line.contains(r#""message":"Kv::get(key: \"b\"): No matching call patterns."#));
+}
+
+#[test]
+fn should_report_plain_and_truncated_values() {
+    let path = report_path("truncated");
+    let long = "x".repeat(1000);
+
+    let result = std::panic::catch_unwind(|| {
+        let u = Unimock::new((
+            KvMock::get.each_call(matching!("a")).returns(Some(1)),
+            KvMock::get.each_call(matching!("b")).returns(Some(2)),
+        ))
+        .with_max_input_len(16)
+        .with_verbosity(Verbosity::Verbose)
+        .with_report(ReportTarget::File(path.clone()));
+
+        u.get(&long);
+    });
+    assert!(result.is_err());
+
+    let lines = read_lines(&path);
+    assert_eq!(1, lines.len());
+    let line = &lines[0];
+    assert!(line.contains(
+        r#"{"pattern_index":0,"pattern_location":"tests/it/report.rs:54","input_index":0,"input_name":"key","kind":"pattern","actual":"\"xxxxxxxxxxxxxxx… 986 more bytes""#
+    ));
+    assert!(line.contains(r#"{"pattern_index":1,"pattern_location":"tests/it/report.rs:55","#));
+    assert!(!line.contains(r#"\u001b"#));
+}
+
+#[test]
+fn should_report_verification_failure_as_json_line() {
+    let path = report_path("verification");
+
+    let result = std::panic::catch_unwind(|| {
+        Unimock::new(KvMock::get.next_call(matching!("a")).returns(Some(1)))
+            .with_report(ReportTarget::File(path.clone()));
+    });
+    assert!(result.is_err());
+
+    let lines = read_lines(&path);
+    assert_eq!(2, lines.len());
+    assert!(lines[0].starts_with(
+        r#"{"error":"call_count_not_matched","function":"Kv::get","pattern":"Kv::get(\"a\") at tests/it/report.rs:"#
+    ));
+    assert!(lines[0].contains(r#""expected_calls":1,"actual_calls":0,"#));
+    assert!(lines[1].starts_with(r#"{"error":"mock_never_called","function":"Kv::get","#));
+}
@@ -0,0 +1,93 @@
+use unimock::*;
+
+use std::sync::Arc;
+
+#[unimock(api=BuilderMock)]
+trait Builder {
+    fn with_timeout(self, timeout: u32) -> Self;
+    fn timeout(&self) -> u32;
+}
+
+#[unimock(api=FactoryMock)]
+trait Factory {
+    fn boxed_clone(&self) -> Box<Self>;
+    fn shared(&self) -> Arc<Self>;
+    fn name(&self) -> String;
+}
+
+#[test]
+fn builder_consuming_self_returns_self() {
+    let builder = Unimock::new((
+        BuilderMock::with_timeout
+            .next_call(matching!(10))
+            .returns_self(),
+        BuilderMock::timeout.next_call(matching!()).returns(10_u32),
+    ));
+
+    assert_eq!(10, builder.with_timeout(10).timeout());
+}
+
+#[test]
+fn boxed_and_arced_self() {
+    let factory = Unimock::new((
+        FactoryMock::boxed_clone
+            .next_call(matching!())
+            .returns_self(),
+        FactoryMock::shared.next_call(matching!()).returns_self(),
+        FactoryMock::name
+            .each_call(matching!())
+            .returns("factory".to_string())
+            .n_times(2),
+    ));
+
+    let boxed = factory.boxed_clone();
+    let shared = factory.shared();
+    drop(factory);
+
+    assert_eq!("factory", boxed.name());
+    assert_eq!("factory", shared.name());
+}
+
+#[test]
+#[should_panic(expected = "Mock for Builder::timeout was never called.")]
+fn returned_self_takes_part_in_verification() {
+    let builder = Unimock::new((
+        BuilderMock::with_timeout
+            .next_call(matching!(10))
+            .returns_self(),
+        BuilderMock::timeout.next_call(matching!()).returns(10_u32),
+    ));
+
+    builder.with_timeout(10);
+}
+
+#[test]
+fn returns_explicitly_configured_unimock() {
+    let factory = Unimock::new(
+        FactoryMock::boxed_clone
+            .next_call(matching!())
+            .returns(Unimock::new(
+                FactoryMock::name
+                    .next_call(matching!())
+                    .returns("other".to_string()),
+            )),
+    );
+
+    assert_eq!("other", factory.boxed_clone().name());
+}
+
+#[test]
+#[should_panic(expected = "Mock for Factory::name was never called.")]
+fn explicitly_configured_unimock_is_verified() {
+    let factory = Unimock::new(
+        FactoryMock::boxed_clone
+            .next_call(matching!())
+            .returns(Unimock::new(
+                FactoryMock::name
+                    .next_call(matching!())
+                    .returns("other".to_string()),
+            )),
+    );
+
+    factory.boxed_clone();
+}
@@ -0,0 +1,124 @@
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span;
+use unimock::*;
+
+#[unimock(api=FactorialMock, unmock_with=[my_factorial(self, input)])]
+trait Factorial {
+    fn factorial(&self, input: u32) -> u32;
+}
+
+fn my_factorial(f: &impl Factorial, input: u32) -> u32 {
+    f.factorial(input - 1) * input
+}
+
+/// Subscriber that renders the fields of every event as a line.
+#[derive(Clone, Default)]
+struct EventCollector(Arc<Mutex<Vec<String>>>);
+
+struct FieldWriter(String);
+
+impl Visit for FieldWriter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        write!(self.0, " {}={value}", field.name()).unwrap();
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        write!(self.0, " {}={value:?}", field.name()).unwrap();
+    }
+}
+
+impl tracing::Subscriber for EventCollector {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        metadata.target() == "unimock"
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut writer = FieldWriter(String::new());
+        event.record(&mut writer);
+        self.0
+            .lock()
+            .unwrap()
+            .push(writer.0.trim_start().to_string());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn should_emit_events_for_answered_unmocked_and_failed_calls() {
+    let collector = EventCollector::default();
+
+    let result = tracing::subscriber::with_default(collector.clone(), || {
+        std::panic::catch_unwind(|| {
+            let u = Unimock::new(FactorialMock::factorial.stub(|each| {
+                each.call(matching!(1)).returns(1_u32);
+                each.call(matching!(4 | 2)).unmocked();
+            }));
+
+            assert_eq!(2, u.factorial(2));
+            u.factorial(4);
+        })
+    });
+    assert!(result.is_err());
+
+    let events = collector.0.lock().unwrap().clone();
+    assert_eq!(4, events.len(), "{events:#?}");
+    assert_eq!(
+        "message=mocked call unmocked function=Factorial::factorial inputs=(input: 2) outcome=unmocked",
+        events[0]
+    );
+    assert!(events[1].starts_with(
+        "message=mocked call answered function=Factorial::factorial inputs=(input: 1) pattern=Factorial::factorial(1) at tests/it/tracing_events.rs:"
+    ));
+    assert!(events[1].ends_with(" outcome=answered"));
+    assert_eq!(
+        "message=mocked call unmocked function=Factorial::factorial inputs=(input: 4) outcome=unmocked",
+        events[2]
+    );
+    assert!(events[3].starts_with(
+        "message=mocked call failed function=Factorial::factorial inputs=(input: 3) pattern=Factorial::factorial(1) at tests/it/tracing_events.rs:"
+    ));
+    assert!(events[3].ends_with(" outcome=failed error=no_matching_call_patterns"));
+}
+
+#[test]
+fn should_not_emit_answered_event_for_failing_responders() {
+    let collector = EventCollector::default();
+
+    let result = tracing::subscriber::with_default(collector.clone(), || {
+        std::panic::catch_unwind(|| {
+            let u = Unimock::new((
+                FactorialMock::factorial
+                    .next_call(matching!(1))
+                    .returns(1_u32),
+                FactorialMock::factorial
+                    .next_call(matching!(2))
+                    .panics("boom"),
+            ));
+
+            assert_eq!(1, u.factorial(1));
+            u.factorial(2);
+        })
+    });
+    assert!(result.is_err());
+
+    let events = collector.0.lock().unwrap().clone();
+    assert_eq!(2, events.len(), "{events:#?}");
+    assert!(events[0].starts_with("message=mocked call answered"));
+    assert!(events[1]
+        .starts_with("message=mocked call failed function=Factorial::factorial inputs=(input: 2)"));
+    assert!(events[1].ends_with(" outcome=failed error=explicit_panic"));
+}
@@ -159,3 +159,61 @@ mod unmock_without_api_should_not_have_shadowing_problems_wrt_unmock_fns {
 
     fn foo(_: &impl std::any::Any) {}
 }
+
+mod unmock_per_method {
+    use super::*;
+
+    #[unimock(api=PerMethodMock)]
+    trait PerMethod {
+        #[unimock(unmock_with = repeat)]
+        fn repeat(&self, arg: String) -> String;
+
+        fn mocked(&self) -> i32;
+
+        #[unimock(unmock_with = add_one(arg))]
+        fn add_one(&self, arg: i32) -> i32;
+    }
+
+    fn repeat(_: &impl Any, arg: String) -> String {
+        format!("{arg}{arg}")
+    }
+
+    fn add_one(arg: i32) -> i32 {
+        arg + 1
+    }
+
+    #[test]
+    fn per_method_unmock_with() {
+        let u = Unimock::new_partial(PerMethodMock::mocked.next_call(matching!()).returns(42));
+
+        assert_eq!("aa", u.repeat("a".to_string()));
+        assert_eq!(42, u.mocked());
+        assert_eq!(2, u.add_one(1));
+    }
+}
+
+mod unmock_consuming_self {
+    use super::*;
+
+    #[unimock(api=ConsumeMock, unmock_with=[consume(self)])]
+    trait Consume {
+        fn consume(self) -> i32;
+    }
+
+    fn consume(_: impl Any) -> i32 {
+        42
+    }
+
+    #[test]
+    fn unmock_with_consuming_self() {
+        let u = Unimock::new(ConsumeMock::consume.next_call(matching!()).unmocked());
+        assert_eq!(42, u.consume());
+    }
+
+    #[test]
+    fn unmock_with_consuming_self_and_call_tree() {
+        let u =
+            Unimock::new(ConsumeMock::consume.next_call(matching!()).unmocked()).with_call_tree();
+        assert_eq!(42, u.consume());
+    }
+}
@@ -17,6 +17,102 @@ pub fn signature_documentation(sig: &syn::Signature, skip_receiver: SkipReceiver
     sig_string
 }
 
+/// Render tokens approximately the way they would be written in source code.
+pub fn source_text(tokens: impl ToTokens) -> String {
+    let mut out = String::new();
+    write_source_text(tokens.into_token_stream(), &mut out);
+    out
+}
+
+fn write_source_text(stream: TokenStream, out: &mut String) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Prev {
+        Start,
+        Operand,
+        Punct,
+    }
+
+    let mut prev = Prev::Start;
+    let mut op = String::new();
+    let mut generics_depth = 0;
+
+    for token in stream {
+        match token {
+            TokenTree::Punct(punct) => {
+                op.push(punct.as_char());
+                if punct.spacing() == proc_macro2::Spacing::Joint {
+                    continue;
+                }
+
+                match op.as_str() {
+                    "." | "::" | "?" | "'" => out.push_str(&op),
+                    // turbofish generics
+                    "<" if out.ends_with("::") => {
+                        generics_depth += 1;
+                        out.push_str(&op);
+                    }
+                    ">" if generics_depth > 0 => {
+                        generics_depth -= 1;
+                        out.push_str(&op);
+                        prev = Prev::Operand;
+                        op.clear();
+                        continue;
+                    }
+                    // macro invocation
+                    "!" if prev == Prev::Operand => out.push_str(&op),
+                    "," | ";" => {
+                        out.push_str(&op);
+                        out.push(' ');
+                    }
+                    _ if prev == Prev::Operand => {
+                        out.push(' ');
+                        out.push_str(&op);
+                        out.push(' ');
+                    }
+                    // unary operator
+                    _ => out.push_str(&op),
+                }
+                prev = if op == "?" {
+                    Prev::Operand
+                } else {
+                    Prev::Punct
+                };
+                op.clear();
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::Brace => (" { ", " }"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                write_source_text(group.stream(), out);
+                out.push_str(close);
+                prev = Prev::Operand;
+            }
+            TokenTree::Ident(ident) => {
+                if prev == Prev::Operand {
+                    out.push(' ');
+                }
+                write!(out, "{ident}").unwrap();
+                prev = Prev::Operand;
+            }
+            TokenTree::Literal(lit) => {
+                if prev == Prev::Operand {
+                    out.push(' ');
+                }
+                write!(out, "{lit}").unwrap();
+                prev = Prev::Operand;
+            }
+        }
+    }
+
+    if out.ends_with(' ') {
+        out.pop();
+    }
+}
+
 macro_rules! doc {
     ($out:expr, [$lit:literal]) => {
         $out.push_str($lit);
@@ -669,4 +765,27 @@ mod tests {
             doc_pat(parse_quote! { (some_macro!()) })
         );
     }
+
+    #[test]
+    fn test_source_text() {
+        fn text(expr: syn::Expr) -> String {
+            super::source_text(expr)
+        }
+
+        assert_eq!("a.len() > *b", text(parse_quote!(a.len() > *b)));
+        assert_eq!(
+            "*id > 10 && name.len() < 5",
+            text(parse_quote!(*id > 10 && name.len() < 5))
+        );
+        assert_eq!("x * 2 == 7", text(parse_quote!(x * 2 == 7)));
+        assert_eq!("!a.is_empty()", text(parse_quote!(!a.is_empty())));
+        assert_eq!(
+            "matches!(a, Some(_)) || b != -1",
+            text(parse_quote!(matches!(a, Some(_)) || b != -1))
+        );
+        assert_eq!(
+            "std::mem::size_of::<u8>()",
+            text(parse_quote!(std::mem::size_of::<u8>()))
+        );
+    }
 }
@@ -15,9 +15,14 @@ pub struct MatchingInput {
 
 struct ArgPattern {
     tuple: syn::PatTuple,
+    /// The names of the inputs matched by each element, e.g. `matching!(user_id: 42)`
+    names: Option<Vec<syn::Ident>>,
+    /// Whether the remaining inputs are ignored by a trailing `..`
+    rest: bool,
 }
 
 struct Arg {
+    index: usize,
     arg_ident: syn::Ident,
     kind: ArgKind,
 }
@@ -52,9 +57,30 @@ pub fn generate(input: MatchingInput) -> proc_macro2::TokenStream {
 
     let args = analyze_args(&input.arg_patterns);
     let pattern_debug_lit_str = generate_pat_debug(&input);
+
+    if args.is_empty() {
+        // Only `..`: any inputs match
+        return quote! {
+            &|_m| {
+                _m.func(|_| true);
+                _m.pat_debug(#pattern_debug_lit_str, file!(), line!());
+            }
+        };
+    }
+
+    let rest = input
+        .arg_patterns
+        .iter()
+        .any(|arg_pattern| arg_pattern.rest);
+    let names = input.arg_patterns[0].names.clone();
     let mut global_guards = vec![];
+    let mut guard_source = None;
 
     if let Some((_, expr)) = input.guard {
+        guard_source = Some(syn::LitStr::new(
+            &crate::doc::source_text(&expr),
+            proc_macro2::Span::call_site(),
+        ));
         global_guards.push(quote! { #expr });
     }
 
@@ -70,28 +96,109 @@ pub fn generate(input: MatchingInput) -> proc_macro2::TokenStream {
         .flat_map(|arm| arm.render_local_defs())
         .collect::<Vec<_>>();
 
-    let arg_pat = concat_args_parenthesized(&args, |arg| {
+    // Definitions evaluated once per call pattern, moved into the matching function:
+    let pattern_defs = arg_pattern_arms
+        .iter()
+        .flat_map(|arm| arm.render_pattern_defs())
+        .collect::<Vec<_>>();
+    // A single input followed by `..` may be the only input, so it is borrowed through the MockFn
+    let rest_first = names.is_none() && rest && args.len() == 1;
+    // Expected values of `eq!` and `ne!` are rendered by the custom debug functions of the MockFn, if it has any
+    let custom_debug = arg_pattern_arms
+        .last()
+        .map(|arm| arm.has_custom_debug_diagnostics())
+        .unwrap_or(false);
+    let custom_debug_def = if custom_debug {
+        Some(quote! {
+            let _d = ::unimock::macro_api::CustomDebug::of(_m);
+        })
+    } else {
+        None
+    };
+    let opt_move = if pattern_defs.is_empty() && names.is_none() && !rest_first && !custom_debug {
+        None
+    } else {
+        Some(quote! { move })
+    };
+
+    let arg_idents = concat_args_parenthesized(&args, |arg| {
         let arg_ident = &arg.arg_ident;
         quote! { #arg_ident }
     });
     let arg_expr = concat_args_parenthesized(&args, |arg| arg.render_expr());
 
-    let diagnostics_arm = if global_guards.is_empty() {
-        Some(generate_diagnostics_arm(&arg_pattern_arms, &args))
-    } else {
-        // If a global guard is present, there is no way to generate
-        // per-argument diagnostics, because there is not way to make that compile
-        None
+    // Inputs matched by name are borrowed from the named inputs of the MockFn
+    let (input_names_def, inputs_pat, named_inputs_stmts) = match &names {
+        Some(names) => {
+            let named_exprs = concat_args_parenthesized(&args, |arg| {
+                let name = &names[arg.index];
+                quote! { _named.#name }
+            });
+            (
+                Some(quote! {
+                    let _n = ::unimock::macro_api::InputNames::of(_m);
+                }),
+                quote! { inputs },
+                Some(quote! {
+                    let _named = _n.borrow(inputs);
+                    reporter.use_input_names(_n.names());
+                    let #arg_idents = #named_exprs;
+                }),
+            )
+        }
+        None if rest_first => {
+            let arg_ident = &args[0].arg_ident;
+            (
+                Some(quote! {
+                    let _n = ::unimock::macro_api::InputNames::of(_m);
+                }),
+                quote! { inputs },
+                Some(quote! {
+                    let #arg_ident = _n.first(inputs);
+                }),
+            )
+        }
+        None if rest => {
+            let arg_idents = args.iter().map(|arg| &arg.arg_ident);
+            (None, quote! { (#(#arg_idents,)* ..) }, None)
+        }
+        None => (None, arg_idents, None),
     };
 
+    let input_index_exprs = args
+        .iter()
+        .map(|arg| match &names {
+            Some(names) => {
+                let name = &names[arg.index];
+                let name_lit = syn::LitStr::new(&name.to_string(), name.span());
+                quote! { _n.index(#name_lit) }
+            }
+            None => {
+                let index = arg.index;
+                quote! { #index }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let diagnostics_arm = generate_diagnostics_arm(
+        &arg_pattern_arms,
+        &args,
+        &input_index_exprs,
+        guard_source.as_ref(),
+    );
+
     let success_arms = arg_pattern_arms
         .iter()
         .map(|match_arm| match_arm.render_success_arm(&global_guards));
 
     quote! {
         &|_m| {
+            #(#pattern_defs)*
+            #input_names_def
+            #custom_debug_def
             _m.debug_func(
-                |#arg_pat, reporter| {
+                #opt_move |#inputs_pat, reporter| {
+                    #named_inputs_stmts
                     #(#local_defs)*
                     match #arg_expr {
                         #(#success_arms)*
@@ -123,13 +230,41 @@ impl ArgPatternArm {
         Self { arg_matchers }
     }
 
+    /// Whether the diagnostics of the arm render expected values, which may need a custom debug function
+    fn has_custom_debug_diagnostics(&self) -> bool {
+        self.arg_matchers.iter().any(|arg_matcher| {
+            matches!(
+                arg_matcher,
+                ArgMatcher::Compare(CompareMatcher {
+                    compare_macro: CompareMacro::Eq | CompareMacro::Ne,
+                    ..
+                })
+            )
+        })
+    }
+
     fn render_local_defs(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
         self.arg_matchers
             .iter()
             .filter_map(|arg_matcher| arg_matcher.render_local_def())
     }
 
+    fn render_pattern_defs(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+        self.arg_matchers
+            .iter()
+            .filter_map(|arg_matcher| arg_matcher.render_pattern_def())
+    }
+
     fn render_success_arm(&self, global_guards: &[TokenStream]) -> proc_macro2::TokenStream {
+        let pattern = self.render_guarded_pattern(global_guards);
+
+        quote! {
+            #pattern => true,
+        }
+    }
+
+    /// The tuple pattern of the arm, guarded by the comparisons of the arm and the global guards
+    fn render_guarded_pattern(&self, global_guards: &[TokenStream]) -> proc_macro2::TokenStream {
         let mut concatenated_guards = Vec::from_iter(global_guards);
 
         let local_guards = self
@@ -158,7 +293,7 @@ impl ArgPatternArm {
         };
 
         quote! {
-            #tuple_ish_pattern #if_guard => true,
+            #tuple_ish_pattern #if_guard
         }
     }
 }
@@ -199,6 +334,10 @@ impl ArgMatcher {
     fn render_local_def(&self) -> Option<proc_macro2::TokenStream> {
         match self {
             Self::Pattern(_) => None,
+            Self::Compare(CompareMatcher {
+                compare_macro: CompareMacro::Regex,
+                ..
+            }) => None,
             Self::Compare(compare_matcher) => {
                 let local_ident = &compare_matcher.local_ident;
                 let tokens = &compare_matcher.tokens;
@@ -210,6 +349,26 @@ impl ArgMatcher {
         }
     }
 
+    fn render_pattern_def(&self) -> Option<proc_macro2::TokenStream> {
+        match self {
+            Self::Compare(
+                compare_matcher @ CompareMatcher {
+                    compare_macro: CompareMacro::Regex,
+                    ..
+                },
+            ) => {
+                let span = compare_matcher.span;
+                let local_ident = &compare_matcher.local_ident;
+                let tokens = &compare_matcher.tokens;
+
+                Some(quote_spanned! { span=>
+                    let #local_ident = ::unimock::macro_api::compile_regex(#tokens);
+                })
+            }
+            _ => None,
+        }
+    }
+
     fn render_match_tuple_elem(&self) -> proc_macro2::TokenStream {
         match self {
             Self::Pattern(pattern) => quote! { #pattern },
@@ -227,15 +386,22 @@ impl ArgMatcher {
                 let span = compare_matcher.span;
                 let pat_bind_ident = &compare_matcher.pat_bind_ident;
                 let local_ident = &compare_matcher.local_ident;
-                let operator = compare_matcher.compare_macro.operator(span);
+                let comparison =
+                    compare_matcher
+                        .compare_macro
+                        .comparison(span, pat_bind_ident, local_ident);
                 Some(quote_spanned! { span=>
-                    (#pat_bind_ident #operator #local_ident)
+                    (#comparison)
                 })
             }
         }
     }
 
-    fn render_diagnostics_stmt(&self, index: usize, arg: &Arg) -> Option<proc_macro2::TokenStream> {
+    fn render_diagnostics_stmt(
+        &self,
+        index: &proc_macro2::TokenStream,
+        arg: &Arg,
+    ) -> Option<proc_macro2::TokenStream> {
         let arg_expr = arg.render_expr();
 
         match self {
@@ -250,6 +416,7 @@ impl ArgMatcher {
 
                     Some(quote! {
                         match #arg_expr {
+                            #[allow(unused_variables)]
                             #pat => {}
                             mismatch => {
                                 use ::unimock::macro_api::{ProperDebug, NoDebug};
@@ -261,21 +428,44 @@ impl ArgMatcher {
             },
             ArgMatcher::Compare(compare_matcher) => {
                 let span = compare_matcher.span;
-                let operator = compare_matcher.compare_macro.operator(span);
                 let local_ident = &compare_matcher.local_ident;
-
-                let reporter_method = syn::Ident::new(
-                    match &compare_matcher.compare_macro {
-                        CompareMacro::Eq => "eq_fail_opt_debug",
-                        CompareMacro::Ne => "ne_fail_opt_debug",
-                    },
-                    span,
-                );
+                let comparison =
+                    compare_matcher
+                        .compare_macro
+                        .comparison(span, &arg_expr, local_ident);
+
+                let (reporter_method, expected) = match &compare_matcher.compare_macro {
+                    CompareMacro::Eq => (
+                        "eq_fail_opt_debug",
+                        quote! { #local_ident.unimock_try_debug() },
+                    ),
+                    CompareMacro::Ne => (
+                        "ne_fail_opt_debug",
+                        quote! { #local_ident.unimock_try_debug() },
+                    ),
+                    CompareMacro::Is => (
+                        "matcher_fail_opt_debug",
+                        quote! { ::unimock::macro_api::describe_matcher(&#local_ident, #arg_expr) },
+                    ),
+                    CompareMacro::Regex => (
+                        "regex_fail_opt_debug",
+                        quote! { ::unimock::macro_api::describe_regex(&#local_ident) },
+                    ),
+                };
+                let reporter_method = syn::Ident::new(reporter_method, span);
+                let custom_expected_stmt = match &compare_matcher.compare_macro {
+                    CompareMacro::Eq | CompareMacro::Ne => Some(quote! {
+                        use ::unimock::macro_api::{ProperCustomDebug, NoCustomDebug};
+                        reporter.custom_expected_debug((&_d.expected(&#local_ident)).unimock_custom_debug(#index));
+                    }),
+                    _ => None,
+                };
 
                 Some(quote! {
-                    if !(#arg_expr #operator #local_ident) {
+                    if !(#comparison) {
                         use ::unimock::macro_api::{ProperDebug, NoDebug};
-                        reporter.#reporter_method(#index, #arg_expr.unimock_try_debug_opt(), #local_ident.unimock_try_debug());
+                        reporter.#reporter_method(#index, #arg_expr.unimock_try_debug_opt(), #expected);
+                        #custom_expected_stmt
                     }
                 })
             }
@@ -291,7 +481,12 @@ struct CompareMatcher {
     tokens: proc_macro2::TokenStream,
 }
 
-fn generate_diagnostics_arm(arms: &[ArgPatternArm], args: &[Arg]) -> proc_macro2::TokenStream {
+fn generate_diagnostics_arm(
+    arms: &[ArgPatternArm],
+    args: &[Arg],
+    input_index_exprs: &[proc_macro2::TokenStream],
+    guard_source: Option<&syn::LitStr>,
+) -> proc_macro2::TokenStream {
     let body = match arms.last() {
         None => quote! { false },
         Some(arm) => {
@@ -300,15 +495,44 @@ fn generate_diagnostics_arm(arms: &[ArgPatternArm], args: &[Arg]) -> proc_macro2
                     .iter()
                     .enumerate()
                     .filter_map(|(index, arg_matcher)| {
-                        arg_matcher.render_diagnostics_stmt(index, &args[index])
+                        arg_matcher.render_diagnostics_stmt(&input_index_exprs[index], &args[index])
                     });
 
-            quote! {
-                {
-                    #(#check_stmts)*
+            match guard_source {
+                // The guard is evaluated separately from the patterns:
+                // If some pattern matched, it must have been the guard that failed.
+                Some(guard_source) => {
+                    let arg_expr = concat_args_parenthesized(args, |arg| arg.render_expr());
+                    let guard_fail_arms = arms.iter().map(|arm| {
+                        let pattern = arm.render_guarded_pattern(&[]);
+                        quote! {
+                            #[allow(unused_variables)]
+                            #pattern => {
+                                reporter.guard_fail(#guard_source);
+                            }
+                        }
+                    });
 
-                    false
+                    quote! {
+                        {
+                            match #arg_expr {
+                                #(#guard_fail_arms)*
+                                _ => {
+                                    #(#check_stmts)*
+                                }
+                            }
+
+                            false
+                        }
+                    }
                 }
+                None => quote! {
+                    {
+                        #(#check_stmts)*
+
+                        false
+                    }
+                },
             }
         }
     };
@@ -321,6 +545,8 @@ fn generate_diagnostics_arm(arms: &[ArgPatternArm], args: &[Arg]) -> proc_macro2
 enum CompareMacro {
     Eq,
     Ne,
+    Is,
+    Regex,
 }
 
 impl CompareMacro {
@@ -329,20 +555,35 @@ impl CompareMacro {
             Some(Self::Eq)
         } else if path.is_ident("ne") {
             Some(Self::Ne)
+        } else if path.is_ident("is") {
+            Some(Self::Is)
+        } else if path.is_ident("regex") {
+            Some(Self::Regex)
         } else {
             None
         }
     }
 
-    fn operator(&self, span: proc_macro2::Span) -> proc_macro2::TokenStream {
+    fn comparison(
+        &self,
+        span: proc_macro2::Span,
+        actual: &impl quote::ToTokens,
+        local_ident: &syn::Ident,
+    ) -> proc_macro2::TokenStream {
         match self {
-            Self::Eq => quote_spanned! { span=> == },
-            Self::Ne => quote_spanned! { span=> != },
+            Self::Eq => quote_spanned! { span=> #actual == #local_ident },
+            Self::Ne => quote_spanned! { span=> #actual != #local_ident },
+            Self::Is => quote_spanned! { span=>
+                ::unimock::macro_api::matcher_matches(&#local_ident, #actual)
+            },
+            Self::Regex => quote_spanned! { span=>
+                ::unimock::macro_api::regex_matches(&#local_ident, #actual)
+            },
         }
     }
 }
 
-fn concat_args_parenthesized<F>(args: &Vec<Arg>, f: F) -> proc_macro2::TokenStream
+fn concat_args_parenthesized<F>(args: &[Arg], f: F) -> proc_macro2::TokenStream
 where
     F: Fn(&Arg) -> proc_macro2::TokenStream,
 {
@@ -368,6 +609,7 @@ fn analyze_args(patterns: &[ArgPattern]) -> Vec<Arg> {
 
     for i in 0..arg_count {
         args.push(Arg {
+            index: i,
             arg_ident: quote::format_ident!("a{}", i),
             kind: guess_arg_kind(i, patterns),
         });
@@ -431,6 +673,28 @@ fn guess_arg_kind(index: usize, patterns: &[ArgPattern]) -> ArgKind {
     }
 }
 
+impl SynDoc for ArgPattern {
+    fn doc(&self, out: &mut String) {
+        out.push('(');
+        for (index, elem) in self.tuple.elems.iter().enumerate() {
+            if index > 0 {
+                out.push_str(", ");
+            }
+            if let Some(names) = &self.names {
+                out.push_str(&format!("{}: ", names[index]));
+            }
+            elem.doc(out);
+        }
+        if self.rest {
+            if !self.tuple.elems.is_empty() {
+                out.push_str(", ");
+            }
+            out.push_str("..");
+        }
+        out.push(')');
+    }
+}
+
 fn generate_pat_debug(input: &MatchingInput) -> syn::LitStr {
     let mut debug = String::new();
 
@@ -440,7 +704,7 @@ fn generate_pat_debug(input: &MatchingInput) -> syn::LitStr {
         let len = input.arg_patterns.len();
 
         for (index, arg_pattern) in input.arg_patterns.iter().enumerate() {
-            arg_pattern.tuple.doc(&mut debug);
+            arg_pattern.doc(&mut debug);
             if index < len - 1 {
                 debug.push_str(" | ");
             }
@@ -493,6 +757,19 @@ mod tests {
         assert_eq!("([1, 2, ..])", test_doc(parse_quote!([1, 2, ..])));
         assert_eq!("(Struct {})", test_doc(parse_quote!(some::Struct { a: b })));
 
+        assert_eq!("(1, ..)", test_doc(parse_quote!(1, ..)));
+        assert_eq!("(..)", test_doc(parse_quote!(..)));
+        assert_eq!(
+            "(1, ..) | (2, ..)",
+            test_doc(parse_quote!((1, ..) | (2, ..)))
+        );
+        assert_eq!("(a: 1, ..)", test_doc(parse_quote!(a: 1, ..)));
+        assert_eq!("(a: 1 | 2, b: _)", test_doc(parse_quote!(a: 1 | 2, b: _)));
+        assert_eq!(
+            "(a: x, ..) if {guard}",
+            test_doc(parse_quote!(a: x, .. if expr()))
+        );
+
         assert_eq!("(1) if {guard}", test_doc(parse_quote!((1) if expr())));
         assert_eq!(
             "(1) | (2) if {guard}",
@@ -506,6 +783,18 @@ mod tests {
         test_doc(parse_quote!((1) | 2));
     }
 
+    #[test]
+    #[should_panic(expected = "Cannot mix named and positional inputs")]
+    fn named_and_positional_inputs_should_not_mix() {
+        test_doc(parse_quote!(a: 1, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "`..` must be the last input")]
+    fn rest_should_be_last() {
+        test_doc(parse_quote!(.., 1));
+    }
+
     #[test]
     #[should_panic(expected = "Expected tuple")]
     fn guard_should_require_tuple() {
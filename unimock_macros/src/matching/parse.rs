@@ -8,9 +8,10 @@ impl syn::parse::Parse for MatchingInput {
         let mut guard = None;
 
         if !input.is_empty() {
+            let first_name = parse_input_name(input)?;
             let first: syn::Pat = parse_pat_with_pipe_if_not_in_tuple(input)?;
 
-            if input.peek(syn::token::Or) {
+            if first_name.is_none() && input.peek(syn::token::Or) {
                 arg_patterns.push(expect_canonical_arg_pattern(first)?);
 
                 while input.peek(syn::token::Or) {
@@ -19,11 +20,35 @@ impl syn::parse::Parse for MatchingInput {
                 }
             } else {
                 let mut elems = syn::punctuated::Punctuated::<syn::Pat, syn::token::Comma>::new();
-                elems.push(first);
+                let mut names = vec![];
+                let mut rest = false;
+
+                let mut push_elem = |name: Option<syn::Ident>, pat: syn::Pat| -> syn::Result<()> {
+                    if rest {
+                        return Err(syn::Error::new(pat.span(), "`..` must be the last input"));
+                    }
+                    if let (None, syn::Pat::Rest(_)) = (&name, &pat) {
+                        rest = true;
+                        return Ok(());
+                    }
+                    if !elems.is_empty() && name.is_some() == names.is_empty() {
+                        return Err(syn::Error::new(
+                            pat.span(),
+                            "Cannot mix named and positional inputs",
+                        ));
+                    }
+
+                    names.extend(name);
+                    elems.push(pat);
+                    Ok(())
+                };
+
+                push_elem(first_name, first)?;
 
                 while input.peek(syn::token::Comma) {
-                    elems.push_punct(input.parse()?);
-                    elems.push(parse_pat_with_pipe_if_not_in_tuple(input)?);
+                    let _: syn::token::Comma = input.parse()?;
+                    let name = parse_input_name(input)?;
+                    push_elem(name, parse_pat_with_pipe_if_not_in_tuple(input)?)?;
                 }
 
                 arg_patterns.push(ArgPattern {
@@ -32,6 +57,8 @@ impl syn::parse::Parse for MatchingInput {
                         paren_token: syn::token::Paren::default(),
                         elems,
                     },
+                    names: if names.is_empty() { None } else { Some(names) },
+                    rest,
                 });
             }
         }
@@ -56,7 +83,30 @@ impl syn::parse::Parse for MatchingInput {
     }
 }
 
+/// Parse the `name:` prefix of an input matched by name, e.g. `user_id: 42`.
+fn parse_input_name(input: syn::parse::ParseStream) -> syn::Result<Option<syn::Ident>> {
+    if input.peek(syn::Ident) && input.peek2(syn::token::Colon) && !input.peek2(syn::token::Colon2)
+    {
+        let name: syn::Ident = input.parse()?;
+        let _: syn::token::Colon = input.parse()?;
+        Ok(Some(name))
+    } else {
+        Ok(None)
+    }
+}
+
 fn parse_pat_with_pipe_if_not_in_tuple(input: syn::parse::ParseStream) -> syn::Result<syn::Pat> {
+    // syn would parse `.. if` as the start of a range pattern
+    if input.peek(syn::token::Dot2) && {
+        let fork = input.fork();
+        fork.parse::<syn::token::Dot2>().is_ok() && fork.peek(syn::token::If)
+    } {
+        return Ok(syn::Pat::Rest(syn::PatRest {
+            attrs: vec![],
+            dot2_token: input.parse()?,
+        }));
+    }
+
     let pat: syn::Pat = input.parse()?;
     if input.peek(syn::token::Or) && !matches!(pat, syn::Pat::Tuple(_)) {
         let mut cases: syn::punctuated::Punctuated<syn::Pat, syn::token::Or> =
@@ -79,7 +129,7 @@ fn parse_pat_with_pipe_if_not_in_tuple(input: syn::parse::ParseStream) -> syn::R
 }
 
 fn try_flatten_if_single_pattern(arg_patterns: &mut Vec<ArgPattern>) -> syn::Result<()> {
-    if arg_patterns.len() != 1 {
+    if arg_patterns.len() != 1 || arg_patterns[0].names.is_some() {
         return Ok(());
     }
 
@@ -102,7 +152,25 @@ fn try_flatten_if_single_pattern(arg_patterns: &mut Vec<ArgPattern>) -> syn::Res
 
 fn expect_canonical_arg_pattern(pat: syn::Pat) -> syn::Result<ArgPattern> {
     match pat {
-        syn::Pat::Tuple(tuple) => Ok(ArgPattern { tuple }),
+        syn::Pat::Tuple(mut tuple) => {
+            let rest = matches!(tuple.elems.last(), Some(syn::Pat::Rest(_)));
+            if rest {
+                tuple.elems.pop();
+            }
+            if let Some(pat @ syn::Pat::Rest(_)) = tuple
+                .elems
+                .iter()
+                .find(|pat| matches!(pat, syn::Pat::Rest(_)))
+            {
+                return Err(syn::Error::new(pat.span(), "`..` must be the last input"));
+            }
+
+            Ok(ArgPattern {
+                tuple,
+                names: None,
+                rest,
+            })
+        }
         _ => Err(syn::Error::new(pat.span(), "Expected tuple")),
     }
 }
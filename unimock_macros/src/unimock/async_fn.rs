@@ -0,0 +1,253 @@
+use syn::visit_mut::VisitMut;
+
+/// How an `async fn` of the mocked trait is implemented.
+///
+/// The location of a call can only be tracked in the synchronous part of a method,
+/// so async methods are implemented as plain functions returning a future.
+pub enum AsyncFn {
+    /// An `async fn` in a trait without `#[async_trait]`, implemented as `fn -> impl Future`.
+    Native,
+    /// An `async fn` in an `#[async_trait]` trait, implemented like `#[async_trait]` would.
+    AsyncTrait { send: bool },
+}
+
+impl AsyncFn {
+    /// The async flavour of the trait, given its `#[async_trait]` attribute, if any.
+    pub fn new(async_trait_attr: Option<&syn::Attribute>) -> Self {
+        match async_trait_attr {
+            Some(attr) => Self::AsyncTrait {
+                send: attr.tokens.to_string().replace(' ', "") != "(?Send)",
+            },
+            None => Self::Native,
+        }
+    }
+
+    /// Turn the signature of an `async fn` into a synchronous signature returning a future.
+    pub fn desugar_sig(
+        &self,
+        trait_generics: &syn::Generics,
+        sig: &syn::Signature,
+    ) -> syn::Signature {
+        let mut sig = sig.clone();
+        sig.asyncness = None;
+
+        let output = match &sig.output {
+            syn::ReturnType::Default => syn::parse_quote! { () },
+            syn::ReturnType::Type(_, ty) => ty.as_ref().clone(),
+        };
+
+        match self {
+            Self::Native => {
+                sig.output = syn::parse_quote! {
+                    -> impl ::core::future::Future<Output = #output>
+                };
+            }
+            Self::AsyncTrait { send } => {
+                desugar_async_trait_sig(trait_generics, &mut sig);
+
+                // Lifetimes elided in the output refer to the now named lifetime of the receiver
+                let mut output = output;
+                if let Some(syn::FnArg::Receiver(syn::Receiver {
+                    reference: Some((_, Some(self_lifetime))),
+                    ..
+                })) = sig.inputs.first()
+                {
+                    NameElidedLifetimes(self_lifetime).visit_type_mut(&mut output);
+                }
+
+                let send_bound = if *send {
+                    Some(quote::quote! { ::core::marker::Send + })
+                } else {
+                    None
+                };
+                sig.output = syn::parse_quote! {
+                    -> ::core::pin::Pin<::std::boxed::Box<
+                        dyn ::core::future::Future<Output = #output> + #send_bound 'async_trait
+                    >>
+                };
+            }
+        }
+
+        sig
+    }
+}
+
+// This must produce the same generics as `#[async_trait]` does for the method in the trait,
+// because the lifetime parameters of the method impl have to match those of the trait method.
+fn desugar_async_trait_sig(trait_generics: &syn::Generics, sig: &mut syn::Signature) {
+    let mut lifetimes = CollectLifetimes::default();
+    let mut has_self = false;
+    for fn_arg in sig.inputs.iter_mut() {
+        match fn_arg {
+            syn::FnArg::Receiver(receiver) => {
+                has_self = true;
+                if let Some((and, lifetime)) = &mut receiver.reference {
+                    lifetimes.visit_opt_lifetime(and, lifetime);
+                }
+            }
+            syn::FnArg::Typed(pat_type) => {
+                if matches!(pat_type.pat.as_ref(), syn::Pat::Ident(pat_ident) if pat_ident.ident == "self")
+                {
+                    has_self = true;
+                }
+                lifetimes.visit_type_mut(&mut pat_type.ty);
+            }
+        }
+    }
+
+    let mut predicates: Vec<syn::WherePredicate> = vec![];
+    for param in sig.generics.params.iter_mut() {
+        match param {
+            syn::GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                if type_param.attrs.is_empty() {
+                    type_param.colon_token = None;
+                    let bounds = std::mem::take(&mut type_param.bounds).into_iter();
+                    predicates.push(syn::parse_quote! { #ident: 'async_trait #(+ #bounds)* });
+                } else {
+                    type_param.bounds.push(syn::parse_quote! { 'async_trait });
+                }
+            }
+            syn::GenericParam::Lifetime(lifetime_def) => {
+                let lifetime = &lifetime_def.lifetime;
+                if lifetime_def.attrs.is_empty() {
+                    lifetime_def.colon_token = None;
+                    let bounds = std::mem::take(&mut lifetime_def.bounds).into_iter();
+                    predicates.push(syn::parse_quote! { #lifetime: 'async_trait #(+ #bounds)* });
+                } else {
+                    lifetime_def.bounds.push(syn::parse_quote! { 'async_trait });
+                }
+            }
+            syn::GenericParam::Const(_) => {}
+        }
+    }
+
+    for lifetime_def in trait_generics.lifetimes() {
+        if lifetimes.explicit.contains(&lifetime_def.lifetime) {
+            let lifetime = &lifetime_def.lifetime;
+            predicates.push(syn::parse_quote! { #lifetime: 'async_trait });
+        }
+    }
+
+    for elided in lifetimes.elided {
+        sig.generics.params.push(syn::parse_quote! { #elided });
+        predicates.push(syn::parse_quote! { #elided: 'async_trait });
+    }
+    sig.generics.params.push(syn::parse_quote! { 'async_trait });
+
+    if has_self {
+        predicates.push(syn::parse_quote! { Self: 'async_trait });
+    }
+
+    sig.generics
+        .make_where_clause()
+        .predicates
+        .extend(predicates);
+
+    for fn_arg in sig.inputs.iter_mut() {
+        match fn_arg {
+            syn::FnArg::Receiver(receiver) => {
+                receiver.mutability = None;
+            }
+            syn::FnArg::Typed(pat_type) => {
+                if !matches!(pat_type.ty.as_ref(), syn::Type::Reference(_)) {
+                    if let syn::Pat::Ident(pat_ident) = pat_type.pat.as_mut() {
+                        pat_ident.by_ref = None;
+                        pat_ident.mutability = None;
+                    }
+                }
+                AddLifetimeToImplTrait.visit_type_mut(&mut pat_type.ty);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct CollectLifetimes {
+    elided: Vec<syn::Lifetime>,
+    explicit: Vec<syn::Lifetime>,
+}
+
+impl CollectLifetimes {
+    fn visit_opt_lifetime(&mut self, and: &syn::token::And, lifetime: &mut Option<syn::Lifetime>) {
+        match lifetime {
+            None => *lifetime = Some(self.next_lifetime(and.span)),
+            Some(lifetime) => self.visit_lifetime(lifetime),
+        }
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.next_lifetime(lifetime.span());
+        } else {
+            self.explicit.push(lifetime.clone());
+        }
+    }
+
+    fn next_lifetime(&mut self, span: proc_macro2::Span) -> syn::Lifetime {
+        let lifetime = syn::Lifetime::new(&format!("'life{}", self.elided.len()), span);
+        self.elided.push(lifetime.clone());
+        lifetime
+    }
+}
+
+impl VisitMut for CollectLifetimes {
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        self.visit_opt_lifetime(&ty.and_token, &mut ty.lifetime);
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_generic_argument_mut(&mut self, arg: &mut syn::GenericArgument) {
+        if let syn::GenericArgument::Lifetime(lifetime) = arg {
+            self.visit_lifetime(lifetime);
+        }
+        syn::visit_mut::visit_generic_argument_mut(self, arg);
+    }
+}
+
+struct NameElidedLifetimes<'l>(&'l syn::Lifetime);
+
+impl<'l> VisitMut for NameElidedLifetimes<'l> {
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.0.clone());
+        }
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.0.clone();
+        }
+    }
+}
+
+struct AddLifetimeToImplTrait;
+
+impl VisitMut for AddLifetimeToImplTrait {
+    fn visit_type_impl_trait_mut(&mut self, ty: &mut syn::TypeImplTrait) {
+        ty.bounds.insert(0, syn::parse_quote! { 'async_trait });
+        syn::visit_mut::visit_type_impl_trait_mut(self, ty);
+    }
+
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        parenthesize_impl_trait(&mut ty.elem);
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_type_ptr_mut(&mut self, ty: &mut syn::TypePtr) {
+        parenthesize_impl_trait(&mut ty.elem);
+        syn::visit_mut::visit_type_ptr_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, _: &mut syn::Expr) {
+        // impl Traits inside of array length expressions are not arguments
+    }
+}
+
+fn parenthesize_impl_trait(elem: &mut Box<syn::Type>) {
+    if let syn::Type::ImplTrait(impl_trait) = elem.as_ref() {
+        let impl_trait = impl_trait.clone();
+        **elem = syn::parse_quote! { (#impl_trait) };
+    }
+}
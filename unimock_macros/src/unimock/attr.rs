@@ -105,6 +105,125 @@ impl syn::parse::Parse for Attr {
     }
 }
 
+/// Parsed `#[unimock(..)]` attributes on a trait method
+#[derive(Default)]
+pub struct MethodAttr {
+    pub unmock_with: Option<WithSpan<UnmockFn>>,
+    pub skip: Option<proc_macro2::Span>,
+    pub name: Option<syn::Ident>,
+}
+
+impl MethodAttr {
+    pub fn extract(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut method_attr = Self::default();
+        for attr in attrs.iter().filter(|attr| is_unimock_attr(attr)) {
+            attr.parse_args_with(|input: syn::parse::ParseStream| {
+                method_attr.parse_keywords(input)
+            })?;
+        }
+        Ok(method_attr)
+    }
+
+    fn parse_keywords(&mut self, input: syn::parse::ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let keyword: syn::Ident = input.parse()?;
+            match keyword.to_string().as_str() {
+                "unmock_with" => {
+                    let _: syn::token::Eq = input.parse()?;
+                    let unmock: Unmock = input.parse()?;
+                    match unmock.0 {
+                        Some(unmock_fn) => {
+                            self.unmock_with = Some(WithSpan(unmock_fn, keyword.span()))
+                        }
+                        None => {
+                            return Err(syn::Error::new(
+                                keyword.span(),
+                                "Expected a path to an unmock function",
+                            ))
+                        }
+                    }
+                }
+                "skip" => {
+                    self.skip = Some(keyword.span());
+                }
+                "name" => {
+                    let _: syn::token::Eq = input.parse()?;
+                    self.name = Some(input.parse()?);
+                }
+                _ => return Err(syn::Error::new(keyword.span(), "Unrecognized keyword")),
+            };
+
+            if input.peek(syn::token::Comma) {
+                let _: syn::token::Comma = input.parse()?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How an input is rendered in debug output, configured by `#[unimock(..)]` on a method parameter
+pub enum ParamDebug {
+    /// `#[unimock(debug_with=path)]`
+    With(syn::Path),
+    /// `#[unimock(redact)]`
+    Redact,
+}
+
+/// Parsed `#[unimock(..)]` attributes on a method parameter
+#[derive(Default)]
+pub struct ParamAttr {
+    pub debug: Option<ParamDebug>,
+}
+
+impl ParamAttr {
+    pub fn extract(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut param_attr = Self::default();
+        for attr in attrs.iter().filter(|attr| is_unimock_attr(attr)) {
+            attr.parse_args_with(|input: syn::parse::ParseStream| {
+                param_attr.parse_keywords(input)
+            })?;
+        }
+        Ok(param_attr)
+    }
+
+    fn parse_keywords(&mut self, input: syn::parse::ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let keyword: syn::Ident = input.parse()?;
+            let debug = match keyword.to_string().as_str() {
+                "debug_with" => {
+                    let _: syn::token::Eq = input.parse()?;
+                    ParamDebug::With(input.parse()?)
+                }
+                "redact" => ParamDebug::Redact,
+                _ => return Err(syn::Error::new(keyword.span(), "Unrecognized keyword")),
+            };
+
+            if self.debug.is_some() {
+                return Err(syn::Error::new(
+                    keyword.span(),
+                    "debug_with and redact cannot be combined",
+                ));
+            }
+            self.debug = Some(debug);
+
+            if input.peek(syn::token::Comma) {
+                let _: syn::token::Comma = input.parse()?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn is_unimock_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("unimock")
+}
+
 pub enum MockApi {
     // User did not provide a mock api,
     // Unimock will still implement the trait but no MockFn types can be named by the user
@@ -1,10 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use quote::quote;
 use syn::spanned::Spanned;
 use syn::visit_mut::VisitMut;
 
-use super::attr::MockApi;
+use super::attr::{self, MethodAttr, MockApi, ParamAttr, ParamDebug, UnmockFn};
 use super::output;
 use super::util::{GenericParamsWithBounds, IsTypeGeneric};
 use super::Attr;
@@ -13,14 +13,20 @@ use crate::doc;
 
 pub struct MockMethod<'t> {
     pub method: &'t syn::TraitItemMethod,
+    pub impl_sig: syn::Signature,
     pub adapted_sig: syn::Signature,
     pub is_type_generic: IsTypeGeneric,
     pub generic_params_with_bounds: GenericParamsWithBounds,
     pub impl_trait_idents: HashSet<String>,
+    /// Indexes of `impl Fn*` inputs, which are passed as `Box<dyn Fn*>` to unimock
+    pub erased_fn_inputs: HashSet<usize>,
+    /// Custom debug rendering of inputs, from `#[unimock(..)]` on parameters, by parameter index
+    pub param_debug: HashMap<usize, ParamDebug>,
     pub non_generic_mock_entry_ident: Option<syn::Ident>,
     pub mock_fn_ident: syn::Ident,
     pub mock_fn_name: syn::LitStr,
     pub output_structure: output::OutputStructure,
+    method_attr: MethodAttr,
     mirrored_attr_indexes: Vec<usize>,
 }
 
@@ -36,30 +42,89 @@ impl<'t> MockMethod<'t> {
         }
     }
 
+    pub fn unmock_fn<'a>(&'a self, index: usize, attr: &'a Attr) -> Option<&'a UnmockFn> {
+        match &self.method_attr.unmock_with {
+            Some(unmock_with) => Some(&unmock_with.0),
+            None => attr.get_unmock_fn(index),
+        }
+    }
+
     pub fn mirrored_attrs(&self) -> impl Iterator<Item = &'_ syn::Attribute> {
         self.mirrored_attr_indexes
             .iter()
             .map(|index| &self.method.attrs[*index])
     }
 
-    pub fn inputs_destructuring(&self) -> InputsDestructuring {
-        InputsDestructuring { method: self }
+    pub fn inputs_destructuring(&self) -> InputsDestructuring<'_> {
+        InputsDestructuring {
+            method: self,
+            box_erased_fns: false,
+        }
+    }
+
+    /// The inputs as expressions passed to unimock, with `impl Fn*` inputs boxed.
+    pub fn inputs_eval_exprs(&self) -> InputsDestructuring<'_> {
+        InputsDestructuring {
+            method: self,
+            box_erased_fns: true,
+        }
+    }
+
+    /// The names of the inputs, excluding the receiver.
+    pub fn input_idents(&self) -> Vec<&syn::Ident> {
+        self.impl_sig
+            .inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, fn_arg)| match fn_arg {
+                syn::FnArg::Receiver(_) => None,
+                syn::FnArg::Typed(pat_type) => match (index, pat_type.pat.as_ref()) {
+                    (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => None,
+                    (_, syn::Pat::Ident(pat_ident)) => Some(&pat_ident.ident),
+                    _ => None,
+                },
+            })
+            .collect()
+    }
+
+    /// The names of the inputs as written in the trait, excluding the receiver.
+    /// Destructured parameters have no name, and are represented by an empty string.
+    pub fn input_names(&self) -> Vec<syn::LitStr> {
+        self.method
+            .sig
+            .inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, fn_arg)| match fn_arg {
+                syn::FnArg::Receiver(_) => None,
+                syn::FnArg::Typed(pat_type) => match (index, pat_type.pat.as_ref()) {
+                    (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => None,
+                    (_, syn::Pat::Ident(pat_ident)) if pat_ident.subpat.is_none() => {
+                        let name = pat_ident.ident.to_string();
+                        Some(syn::LitStr::new(
+                            name.trim_start_matches("r#"),
+                            pat_ident.ident.span(),
+                        ))
+                    }
+                    _ => Some(syn::LitStr::new("", pat_type.span())),
+                },
+            })
+            .collect()
     }
 
     pub fn generate_debug_inputs_fn(&self, attr: &Attr) -> proc_macro2::TokenStream {
         let prefix = &attr.prefix;
         let first_param = self
-            .method
-            .sig
+            .impl_sig
             .inputs
             .iter()
             .find(|fn_arg| matches!(fn_arg, syn::FnArg::Typed(_)));
 
         let body = if first_param.is_some() {
-            let inputs_try_debug_exprs = self.inputs_try_debug_exprs();
+            let inputs_try_debug_exprs = self.inputs_try_debug_exprs(prefix);
             quote! {
                 use #prefix::macro_api::{ProperDebug, NoDebug};
-                #prefix::macro_api::format_inputs(&[#(#inputs_try_debug_exprs),*])
+                #prefix::macro_api::format_named_inputs(Self::INPUT_NAMES, &[#(#inputs_try_debug_exprs),*])
             }
         } else {
             quote! {
@@ -76,19 +141,179 @@ impl<'t> MockMethod<'t> {
         }
     }
 
-    pub fn inputs_try_debug_exprs(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + 't {
-        self.method
-            .sig
+    /// Generate the `debug_input` function of the MockFn, rendering a single input.
+    pub fn generate_debug_input_fn(&self, attr: &Attr) -> proc_macro2::TokenStream {
+        let prefix = &attr.prefix;
+        let arms = self
+            .inputs_try_debug_opt_exprs(prefix)
+            .enumerate()
+            .map(|(input_index, expr)| quote! { #input_index => #expr, })
+            .collect::<Vec<_>>();
+
+        if arms.is_empty() {
+            return quote! {};
+        }
+
+        let inputs_destructuring = self.inputs_destructuring();
+
+        quote! {
+            #[allow(unused_variables)]
+            fn debug_input((#inputs_destructuring): &Self::Inputs<'_>, index: usize) -> ::core::option::Option<String> {
+                use #prefix::macro_api::{ProperDebug, NoDebug};
+                match index {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    }
+
+    /// Generate the `custom_debug_input` function of the MockFn, if any parameter has a custom debug rendering.
+    pub fn generate_custom_debug_input_fn(&self, attr: &Attr) -> proc_macro2::TokenStream {
+        if self.param_debug.is_empty() {
+            return quote! {};
+        }
+
+        let prefix = &attr.prefix;
+        let arms = self
+            .custom_debug_params()
+            .map(|(input_index, pat_ident, ty, param_debug)| {
+                let expr = custom_debug_expr(prefix, param_debug, pat_ident, ty);
+                quote! { #input_index => ::core::option::Option::Some(#expr), }
+            });
+
+        let inputs_destructuring = self.inputs_destructuring();
+
+        quote! {
+            #[allow(unused_variables)]
+            fn custom_debug_input((#inputs_destructuring): &Self::Inputs<'_>, index: usize) -> ::core::option::Option<String> {
+                match index {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    }
+
+    /// Implementations of `DebugExpected`, rendering the expected values of `eq!` and `ne!` with the `debug_with` function of the input.
+    ///
+    /// Only inputs whose innermost referenced type has no lifetimes are supported,
+    /// for other inputs the expected value is not shown.
+    pub fn generate_debug_expected_impls(
+        &self,
+        attr: &Attr,
+        mock_fn_path: &proc_macro2::TokenStream,
+    ) -> Vec<proc_macro2::TokenStream> {
+        let prefix = &attr.prefix;
+
+        // The arms of the inputs sharing the same type are grouped into one implementation
+        let mut arms_by_type: Vec<(&syn::Type, String, Vec<proc_macro2::TokenStream>)> = vec![];
+        for (input_index, _, ty, param_debug) in self.custom_debug_params() {
+            let path = match param_debug {
+                ParamDebug::With(path) => path,
+                ParamDebug::Redact => continue,
+            };
+            let innermost_ty = innermost_referenced_type(ty);
+            if !is_lifetime_free(innermost_ty) {
+                continue;
+            }
+
+            let type_key = quote! { #innermost_ty }.to_string();
+            let arm = quote! { #input_index => ::core::option::Option::Some(#path(expected)), };
+            match arms_by_type.iter_mut().find(|(_, key, _)| *key == type_key) {
+                Some((_, _, arms)) => arms.push(arm),
+                None => arms_by_type.push((innermost_ty, type_key, vec![arm])),
+            }
+        }
+
+        arms_by_type
+            .into_iter()
+            .flat_map(|(ty, _, arms)| {
+                [
+                    quote! {
+                        impl #prefix::macro_api::DebugExpected<#ty> for #mock_fn_path {
+                            fn debug_expected(index: usize, expected: &#ty) -> ::core::option::Option<String> {
+                                match index {
+                                    #(#arms)*
+                                    _ => ::core::option::Option::None,
+                                }
+                            }
+                        }
+                    },
+                    quote! {
+                        impl<'__e> #prefix::macro_api::DebugExpected<&'__e #ty> for #mock_fn_path {
+                            fn debug_expected(index: usize, expected: &&'__e #ty) -> ::core::option::Option<String> {
+                                <Self as #prefix::macro_api::DebugExpected<#ty>>::debug_expected(index, *expected)
+                            }
+                        }
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// The inputs with a custom debug rendering, by input index
+    fn custom_debug_params(
+        &self,
+    ) -> impl Iterator<Item = (usize, &syn::PatIdent, &syn::Type, &ParamDebug)> + '_ {
+        self.impl_sig
             .inputs
             .iter()
             .enumerate()
             .filter_map(|(index, fn_arg)| match fn_arg {
+                syn::FnArg::Typed(pat_type) => match (index, pat_type.pat.as_ref()) {
+                    (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => None,
+                    (_, syn::Pat::Ident(pat_ident)) => Some((index, pat_ident, &pat_type.ty)),
+                    _ => None,
+                },
+                syn::FnArg::Receiver(_) => None,
+            })
+            .enumerate()
+            .filter_map(|(input_index, (index, pat_ident, ty))| {
+                let param_debug = self.param_debug.get(&index)?;
+                Some((input_index, pat_ident, ty.as_ref(), param_debug))
+            })
+    }
+
+    pub fn inputs_try_debug_exprs<'s>(
+        &'s self,
+        prefix: &'s syn::Path,
+    ) -> impl Iterator<Item = proc_macro2::TokenStream> + 's {
+        self.inputs_debug_exprs(prefix, TryDebug::Always)
+    }
+
+    /// Like [Self::inputs_try_debug_exprs], but evaluating to `Option<String>`, which is `None` for inputs without a debug representation.
+    pub fn inputs_try_debug_opt_exprs<'s>(
+        &'s self,
+        prefix: &'s syn::Path,
+    ) -> impl Iterator<Item = proc_macro2::TokenStream> + 's {
+        self.inputs_debug_exprs(prefix, TryDebug::Opt)
+    }
+
+    fn inputs_debug_exprs<'s>(
+        &'s self,
+        prefix: &'s syn::Path,
+        try_debug: TryDebug,
+    ) -> impl Iterator<Item = proc_macro2::TokenStream> + 's {
+        self.impl_sig
+            .inputs
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, fn_arg)| match fn_arg {
                 syn::FnArg::Receiver(_) => None,
                 syn::FnArg::Typed(pat_type) => match (index, pat_type.pat.as_ref()) {
                     (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => None,
-                    (_, syn::Pat::Ident(pat_ident)) => {
-                        Some(try_debug_expr(pat_ident, &pat_type.ty))
-                    }
+                    (_, syn::Pat::Ident(pat_ident)) => match self.param_debug.get(&index) {
+                        Some(param_debug) => {
+                            let expr =
+                                custom_debug_expr(prefix, param_debug, pat_ident, &pat_type.ty);
+                            Some(match try_debug {
+                                TryDebug::Always => expr,
+                                TryDebug::Opt => quote! { ::core::option::Option::Some(#expr) },
+                            })
+                        }
+                        None => Some(try_debug_expr(pat_ident, &pat_type.ty, try_debug)),
+                    },
                     _ => Some(
                         syn::Error::new(pat_type.span(), "Unprocessable argument")
                             .to_compile_error(),
@@ -129,19 +354,43 @@ pub fn extract_methods<'s>(
         })
         .enumerate()
         .map(|(index, method)| {
-            match determine_mockable(method) {
+            let method_attr = MethodAttr::extract(&method.attrs)?;
+
+            match determine_mockable(method, &method_attr) {
                 Mockable::Yes => {}
                 Mockable::Skip => return Ok(None),
                 Mockable::Err(err) => return Err(err),
             };
 
+            if let Some(unmock_with) = &method_attr.unmock_with {
+                if attr.get_unmock_fn(index).is_some() {
+                    return Err(syn::Error::new(
+                        unmock_with.1,
+                        "unmock_with is already specified for this method in the trait attribute",
+                    ));
+                }
+            }
+
             let mock_fn_name = syn::LitStr::new(
                 &format!("{}::{}", &item_trait.ident, method.sig.ident),
                 item_trait.ident.span(),
             );
 
+            let mut param_debug = HashMap::new();
+            for (index, fn_arg) in method.sig.inputs.iter().enumerate() {
+                if let syn::FnArg::Typed(pat_type) = fn_arg {
+                    if let Some(debug) = ParamAttr::extract(&pat_type.attrs)?.debug {
+                        param_debug.insert(index, debug);
+                    }
+                }
+            }
+
+            let mut impl_sig = rename_pattern_params(&method.sig);
+            strip_param_attrs(&mut impl_sig);
+
             let mut adapted_sig = method.sig.clone();
-            let adapt_sig_result = adapt_sig(&mut adapted_sig);
+            strip_param_attrs(&mut adapted_sig);
+            let adapt_sig_result = adapt_sig(prefix, &mut adapted_sig);
             let is_type_generic =
                 IsTypeGeneric(is_trait_type_generic.0 || adapt_sig_result.is_type_generic.0);
 
@@ -166,13 +415,17 @@ pub fn extract_methods<'s>(
 
             Ok(Some(MockMethod {
                 method,
+                impl_sig,
                 adapted_sig,
                 is_type_generic: adapt_sig_result.is_type_generic,
                 generic_params_with_bounds,
                 impl_trait_idents: adapt_sig_result.impl_trait_idents,
+                erased_fn_inputs: adapt_sig_result.erased_fn_inputs,
+                param_debug,
                 non_generic_mock_entry_ident: if is_type_generic.0 {
                     Some(generate_mock_fn_ident(
                         method,
+                        &method_attr,
                         index,
                         IsTypeGeneric(false),
                         attr,
@@ -180,9 +433,16 @@ pub fn extract_methods<'s>(
                 } else {
                     None
                 },
-                mock_fn_ident: generate_mock_fn_ident(method, index, is_type_generic, attr)?,
+                mock_fn_ident: generate_mock_fn_ident(
+                    method,
+                    &method_attr,
+                    index,
+                    is_type_generic,
+                    attr,
+                )?,
                 mock_fn_name,
                 output_structure,
+                method_attr,
                 mirrored_attr_indexes,
             }))
         })
@@ -195,7 +455,7 @@ enum Mockable {
     Err(syn::Error),
 }
 
-fn determine_mockable(method: &syn::TraitItemMethod) -> Mockable {
+fn determine_mockable(method: &syn::TraitItemMethod, method_attr: &MethodAttr) -> Mockable {
     fn is_receiver(first_fn_arg: Option<&syn::FnArg>) -> bool {
         match first_fn_arg {
             None => false,
@@ -208,6 +468,17 @@ fn determine_mockable(method: &syn::TraitItemMethod) -> Mockable {
         }
     }
 
+    if let Some(skip_span) = method_attr.skip {
+        return if method.default.is_some() {
+            Mockable::Skip
+        } else {
+            Mockable::Err(syn::Error::new(
+                skip_span,
+                "Only methods with a default body can be skipped.",
+            ))
+        };
+    }
+
     let first_fn_arg = method.sig.inputs.first();
 
     if is_receiver(first_fn_arg) {
@@ -225,10 +496,19 @@ fn determine_mockable(method: &syn::TraitItemMethod) -> Mockable {
 
 fn generate_mock_fn_ident(
     method: &syn::TraitItemMethod,
+    method_attr: &MethodAttr,
     method_index: usize,
     generic: IsTypeGeneric,
     attr: &Attr,
 ) -> syn::Result<syn::Ident> {
+    if let Some(name) = &method_attr.name {
+        return Ok(match (&attr.mock_api, generic.0) {
+            (_, true) => quote::format_ident!("__Generic{}", name),
+            (MockApi::Hidden, false) => quote::format_ident!("UnimockHidden__{}", name),
+            (MockApi::MockMod(_) | MockApi::Flattened(_), false) => name.clone(),
+        });
+    }
+
     if generic.0 {
         match &attr.mock_api {
             MockApi::Flattened(flat_mocks) => Ok(quote::format_ident!(
@@ -248,47 +528,115 @@ fn generate_mock_fn_ident(
     }
 }
 
-fn try_debug_expr(pat_ident: &syn::PatIdent, ty: &syn::Type) -> proc_macro2::TokenStream {
-    fn count_references(ty: &syn::Type) -> usize {
-        match ty {
-            syn::Type::Reference(type_reference) => 1 + count_references(&type_reference.elem),
-            _ => 0,
+fn count_references(ty: &syn::Type) -> usize {
+    match ty {
+        syn::Type::Reference(type_reference) => 1 + count_references(&type_reference.elem),
+        _ => 0,
+    }
+}
+
+fn innermost_referenced_type(ty: &syn::Type) -> &syn::Type {
+    match ty {
+        syn::Type::Reference(type_reference) => innermost_referenced_type(&type_reference.elem),
+        _ => ty,
+    }
+}
+
+/// Whether the type can be named without lifetimes, including the implicit lifetimes of references and trait objects
+fn is_lifetime_free(ty: &syn::Type) -> bool {
+    fn check(tokens: proc_macro2::TokenStream) -> bool {
+        tokens.into_iter().all(|token| match token {
+            proc_macro2::TokenTree::Group(group) => check(group.stream()),
+            proc_macro2::TokenTree::Punct(punct) => !matches!(punct.as_char(), '&' | '\''),
+            proc_macro2::TokenTree::Ident(ident) => ident != "dyn" && ident != "impl",
+            proc_macro2::TokenTree::Literal(_) => true,
+        })
+    }
+
+    check(quote! { #ty })
+}
+
+fn custom_debug_expr(
+    prefix: &syn::Path,
+    param_debug: &ParamDebug,
+    pat_ident: &syn::PatIdent,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let ident = &pat_ident.ident;
+    match param_debug {
+        ParamDebug::With(path) => {
+            // the function receives a reference to the innermost referenced value
+            let derefs = (0..count_references(ty)).map(|_| quote! { * });
+            quote! {
+                #path(#(#derefs)* #ident)
+            }
         }
+        ParamDebug::Redact => quote! {
+            {
+                let _ = #ident;
+                ::std::string::String::from(#prefix::macro_api::REDACTED)
+            }
+        },
     }
+}
+
+#[derive(Clone, Copy)]
+enum TryDebug {
+    /// Render as `String`, with `"?"` for inputs without a debug representation
+    Always,
+    /// Render as `Option<String>`
+    Opt,
+}
 
+fn try_debug_expr(
+    pat_ident: &syn::PatIdent,
+    ty: &syn::Type,
+    try_debug: TryDebug,
+) -> proc_macro2::TokenStream {
     let ref_count = count_references(ty);
     let ident = &pat_ident.ident;
+    let method = match try_debug {
+        TryDebug::Always => quote! { unimock_try_debug },
+        TryDebug::Opt => quote! { unimock_try_debug_opt },
+    };
 
     if ref_count > 0 {
         // insert as many * as there are references
         let derefs = (0..ref_count).map(|_| quote! { * });
 
         quote! {
-            (#(#derefs)* #ident).unimock_try_debug()
+            (#(#derefs)* #ident).#method()
         }
     } else {
         quote! {
-            #ident.unimock_try_debug()
+            #ident.#method()
         }
     }
 }
 
 pub struct InputsDestructuring<'t> {
     method: &'t MockMethod<'t>,
+    box_erased_fns: bool,
 }
 
 impl<'t> quote::ToTokens for InputsDestructuring<'t> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let inputs = &self.method.method.sig.inputs;
+        let inputs = &self.method.impl_sig.inputs;
         if inputs.is_empty() {
             return;
         }
 
-        let last_index = self.method.method.sig.inputs.len() - 1;
-        for (index, pair) in self.method.method.sig.inputs.pairs().enumerate() {
+        let last_index = inputs.len() - 1;
+        for (index, pair) in inputs.pairs().enumerate() {
             if let syn::FnArg::Typed(pat_type) = pair.value() {
                 match (index, pat_type.pat.as_ref()) {
                     (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => {}
+                    (_, syn::Pat::Ident(pat_ident))
+                        if self.box_erased_fns && self.method.erased_fn_inputs.contains(&index) =>
+                    {
+                        let ident = &pat_ident.ident;
+                        quote! { ::std::boxed::Box::new(#ident) }.to_tokens(tokens);
+                    }
                     (_, syn::Pat::Ident(pat_ident)) => {
                         pat_ident.to_tokens(tokens);
                     }
@@ -306,17 +654,62 @@ impl<'t> quote::ToTokens for InputsDestructuring<'t> {
     }
 }
 
+/// Parameter attributes consumed by unimock must not be part of generated signatures.
+pub fn strip_param_attrs(sig: &mut syn::Signature) {
+    for fn_arg in sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            pat_type.attrs.retain(|attr| !attr::is_unimock_attr(attr));
+        }
+    }
+}
+
+/// Rename parameters which are not plain identifiers (i.e. destructuring patterns),
+/// so that every input can be referred to by name in the generated method body.
+/// The original pattern is applied in the body of a default method, so it does not need to be preserved here.
+fn rename_pattern_params(sig: &syn::Signature) -> syn::Signature {
+    let mut sig = sig.clone();
+    for (index, fn_arg) in sig.inputs.iter_mut().enumerate() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) if pat_ident.subpat.is_none() => {}
+                _ => {
+                    let ident = quote::format_ident!("__arg{}", index);
+                    *pat_type.pat = syn::parse_quote! { #ident };
+                }
+            }
+        }
+    }
+    sig
+}
+
 struct AdaptSigResult {
     is_type_generic: IsTypeGeneric,
     impl_trait_idents: HashSet<String>,
+    erased_fn_inputs: HashSet<usize>,
 }
 
 // TODO: Rewrite impl Trait to normal param
-fn adapt_sig(sig: &mut syn::Signature) -> AdaptSigResult {
+fn adapt_sig(prefix: &syn::Path, sig: &mut syn::Signature) -> AdaptSigResult {
     let mut generics: syn::Generics = Default::default();
     let mut impl_trait_idents: HashSet<String> = HashSet::new();
     std::mem::swap(&mut sig.generics, &mut generics);
 
+    // `impl Fn*` inputs are erased into boxed trait objects, so that the MockFn is not generic over closure types,
+    // and so that responders are able to call them:
+    let mut erased_fn_inputs: HashSet<usize> = HashSet::new();
+    for (index, fn_arg) in sig.inputs.iter_mut().enumerate() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            if let syn::Type::ImplTrait(impl_trait) = pat_type.ty.as_ref() {
+                if let Some(dyn_bounds) = erased_fn_bounds(&impl_trait.bounds) {
+                    *pat_type.ty = syn::parse_quote! {
+                        ::std::boxed::Box<dyn #(#dyn_bounds)+* + '_>
+                    };
+                    erased_fn_inputs.insert(index);
+                }
+            }
+        }
+    }
+
     struct ImplTraitConverter<'s> {
         generics: &'s mut syn::Generics,
         impl_trait_idents: &'s mut HashSet<String>,
@@ -358,6 +751,33 @@ fn adapt_sig(sig: &mut syn::Signature) -> AdaptSigResult {
     // write back generics
     std::mem::swap(&mut generics, &mut sig.generics);
 
+    // The implementor of the trait is `Unimock`, so `Self` in inputs and outputs means `Unimock`:
+    struct SelfConverter<'s> {
+        prefix: &'s syn::Path,
+    }
+
+    impl<'s> syn::visit_mut::VisitMut for SelfConverter<'s> {
+        fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+            match ty {
+                syn::Type::Path(type_path)
+                    if type_path.qself.is_none() && type_path.path.is_ident("Self") =>
+                {
+                    let prefix = self.prefix;
+                    *ty = syn::parse_quote!( #prefix::Unimock );
+                }
+                _ => syn::visit_mut::visit_type_mut(self, ty),
+            }
+        }
+    }
+
+    let mut self_converter = SelfConverter { prefix };
+    for fn_arg in sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            self_converter.visit_type_mut(&mut pat_type.ty);
+        }
+    }
+    self_converter.visit_return_type_mut(&mut sig.output);
+
     let mut is_type_generic = IsTypeGeneric(false);
     for generic_param in &sig.generics.params {
         if matches!(generic_param, syn::GenericParam::Type(_)) {
@@ -368,5 +788,39 @@ fn adapt_sig(sig: &mut syn::Signature) -> AdaptSigResult {
     AdaptSigResult {
         is_type_generic,
         impl_trait_idents,
+        erased_fn_inputs,
+    }
+}
+
+/// The trait object bounds of an `impl Fn*` type, if it can be erased into a trait object.
+///
+/// This requires exactly one `Fn`, `FnMut` or `FnOnce` bound, and other trait bounds must be auto traits.
+/// Lifetime bounds are removed.
+fn erased_fn_bounds(
+    bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::token::Add>,
+) -> Option<Vec<&syn::TraitBound>> {
+    let mut fn_bound_count = 0;
+    let mut trait_bounds = vec![];
+
+    for bound in bounds {
+        if let syn::TypeParamBound::Trait(trait_bound) = bound {
+            let last_segment = trait_bound.path.segments.last()?;
+            match last_segment.ident.to_string().as_str() {
+                "Fn" | "FnMut" | "FnOnce"
+                    if matches!(last_segment.arguments, syn::PathArguments::Parenthesized(_)) =>
+                {
+                    fn_bound_count += 1;
+                }
+                "Send" | "Sync" | "Unpin" => {}
+                _ => return None,
+            }
+            trait_bounds.push(trait_bound);
+        }
+    }
+
+    if fn_bound_count == 1 {
+        Some(trait_bounds)
+    } else {
+        None
     }
 }
@@ -1,6 +1,7 @@
 use quote::quote;
 
 mod associated_future;
+mod async_fn;
 mod attr;
 mod method;
 mod output;
@@ -20,20 +21,7 @@ pub fn generate(attr: Attr, item_trait: syn::ItemTrait) -> syn::Result<proc_macr
 
     let prefix = &attr.prefix;
     let trait_ident = &trait_info.item.ident;
-    let impl_attributes = trait_info
-        .item
-        .attrs
-        .iter()
-        .filter(|attribute| match attribute.style {
-            syn::AttrStyle::Outer => {
-                if let Some(last_segment) = attribute.path.segments.last() {
-                    last_segment.ident == "async_trait"
-                } else {
-                    false
-                }
-            }
-            syn::AttrStyle::Inner(_) => false,
-        });
+    let impl_attributes = find_async_trait_attr(trait_info.item);
 
     let mock_fn_defs: Vec<Option<MockFnDef>> = trait_info
         .methods
@@ -93,8 +81,10 @@ pub fn generate(attr: Attr, item_trait: syn::ItemTrait) -> syn::Result<proc_macr
         ),
     };
 
+    let output_trait = strip_method_attrs(&item_trait);
+
     Ok(quote! {
-        #item_trait
+        #output_trait
         #opt_mock_interface_public
 
         // private part:
@@ -102,7 +92,7 @@ pub fn generate(attr: Attr, item_trait: syn::ItemTrait) -> syn::Result<proc_macr
             #opt_mock_interface_private
             #(#mock_fn_impl_details)*
 
-            #(#impl_attributes)*
+            #impl_attributes
             impl #generic_params #trait_ident #generic_args for #prefix::Unimock #where_clause {
                 #(#associated_futures)*
                 #(#method_impls)*
@@ -111,6 +101,18 @@ pub fn generate(attr: Attr, item_trait: syn::ItemTrait) -> syn::Result<proc_macr
     })
 }
 
+/// Method attributes consumed by unimock must not be part of the output trait.
+fn strip_method_attrs(item_trait: &syn::ItemTrait) -> syn::ItemTrait {
+    let mut item_trait = item_trait.clone();
+    for item in &mut item_trait.items {
+        if let syn::TraitItem::Method(method) = item {
+            method.attrs.retain(|attr| !attr::is_unimock_attr(attr));
+            method::strip_param_attrs(&mut method.sig);
+        }
+    }
+    item_trait
+}
+
 struct MockFnDef {
     mock_fn_struct_item: proc_macro2::TokenStream,
     impl_details: proc_macro2::TokenStream,
@@ -123,7 +125,7 @@ fn def_mock_fn(
 ) -> Option<MockFnDef> {
     let method = method?;
     let prefix = &attr.prefix;
-    let mirrored_attrs = method.mirrored_attrs();
+    let mirrored_attrs = method.mirrored_attrs().collect::<Vec<_>>();
     let mock_fn_ident = &method.mock_fn_ident;
     let mock_fn_path = method.mock_fn_path(attr);
     let mock_fn_name = &method.mock_fn_name;
@@ -137,7 +139,8 @@ fn def_mock_fn(
 
     let input_lifetime = &attr.input_lifetime;
 
-    let inputs_tuple = method
+    // The non-self inputs, with their index in the signature
+    let indexed_inputs = method
         .adapted_sig
         .inputs
         .iter()
@@ -146,10 +149,14 @@ fn def_mock_fn(
             syn::FnArg::Receiver(_) => None,
             syn::FnArg::Typed(pat_type) => match (index, pat_type.pat.as_ref()) {
                 (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => None,
-                _ => Some(pat_type.ty.as_ref()),
+                _ => Some((index, pat_type.ty.as_ref())),
             },
         })
-        .map(|ty| util::substitute_lifetimes(ty, input_lifetime))
+        .collect::<Vec<_>>();
+
+    let inputs_tuple = indexed_inputs
+        .iter()
+        .map(|(_, ty)| util::substitute_lifetimes(ty, input_lifetime))
         .collect::<Vec<_>>();
 
     let generic_params = util::Generics::params(trait_info, Some(method));
@@ -166,6 +173,66 @@ fn def_mock_fn(
     let output_associated_type = method.output_structure.output_associated_type(prefix);
 
     let debug_inputs_fn = method.generate_debug_inputs_fn(attr);
+    let debug_input_fn = method.generate_debug_input_fn(attr);
+    let custom_debug_input_fn = method.generate_custom_debug_input_fn(attr);
+
+    let input_idents = method.input_idents();
+    let input_names = method.input_names();
+    let named_inputs_ident = quote::format_ident!("{}__inputs", mock_fn_ident);
+
+    // Expected values are only rendered by custom debug functions of non-generic MockFns
+    let debug_expected_impls = if method.non_generic_mock_entry_ident.is_some() {
+        vec![]
+    } else {
+        method
+            .generate_debug_expected_impls(attr, &mock_fn_path)
+            .into_iter()
+            .map(|debug_expected_impl| {
+                quote! {
+                    #(#mirrored_attrs)*
+                    #debug_expected_impl
+                }
+            })
+            .collect()
+    };
+
+    // Named inputs borrow each input by the name of its parameter.
+    // Generic MockFns do not support named inputs.
+    let (named_inputs_struct, named_inputs_impl) = if method.non_generic_mock_entry_ident.is_some()
+    {
+        (None, None)
+    } else {
+        let inputs_destructuring = method.inputs_destructuring();
+        (
+            Some(quote! {
+                #(#mirrored_attrs)*
+                #[allow(non_camel_case_types)]
+                pub struct #named_inputs_ident<'__a, #input_lifetime> {
+                    #(pub #input_idents: &'__a #inputs_tuple,)*
+                    #[doc(hidden)]
+                    pub __inputs: ::core::marker::PhantomData<&'__a &#input_lifetime ()>,
+                }
+            }),
+            Some(quote! {
+                #(#mirrored_attrs)*
+                impl #prefix::macro_api::NamedInputs for #mock_fn_path {
+                    type Named<'__a, #input_lifetime> = #named_inputs_ident<'__a, #input_lifetime> where #input_lifetime: '__a;
+
+                    fn named_inputs<'__a, #input_lifetime>(
+                        (#inputs_destructuring): &'__a Self::Inputs<#input_lifetime>
+                    ) -> Self::Named<'__a, #input_lifetime>
+                    where
+                        #input_lifetime: '__a
+                    {
+                        #named_inputs_ident {
+                            #(#input_idents,)*
+                            __inputs: ::core::marker::PhantomData,
+                        }
+                    }
+                }
+            }),
+        )
+    };
 
     let gen_mock_fn_struct_item = |non_generic_ident: &syn::Ident| {
         quote! {
@@ -175,6 +242,71 @@ fn def_mock_fn(
         }
     };
 
+    // A single callback input can be invoked by responders
+    let callback_ty = if method.erased_fn_inputs.len() == 1 {
+        indexed_inputs
+            .iter()
+            .zip(inputs_tuple.iter())
+            .find(|((index, _), _)| method.erased_fn_inputs.contains(index))
+            .map(|(_, ty)| ty)
+    } else {
+        None
+    };
+    let callback_arg_impl = if let Some(callback_ty) = &callback_ty {
+        let callback_pats = indexed_inputs.iter().map(|(index, _)| {
+            if method.erased_fn_inputs.contains(index) {
+                quote! { callback }
+            } else {
+                quote! { _ }
+            }
+        });
+
+        Some(quote! {
+            #(#mirrored_attrs)*
+            impl #generic_params #prefix::macro_api::CallbackArg for #mock_fn_path #generic_args #where_clause {
+                type Arg<#input_lifetime> = #callback_ty;
+
+                fn callback_arg<#input_lifetime>(inputs: Self::Inputs<#input_lifetime>) -> Self::Arg<#input_lifetime> {
+                    let (#(#callback_pats),*) = inputs;
+                    callback
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    // The first input can be matched without knowing the number of inputs
+    let first_ty = inputs_tuple.first();
+    let first_input_impl = first_ty.map(|first_ty| {
+        let first_input = if inputs_tuple.len() == 1 {
+            quote! { inputs }
+        } else {
+            quote! { &inputs.0 }
+        };
+
+        quote! {
+            #(#mirrored_attrs)*
+            impl #generic_params #prefix::macro_api::FirstInput for #mock_fn_path #generic_args #where_clause {
+                type First<#input_lifetime> = #first_ty;
+
+                fn first_input<'__a, #input_lifetime>(inputs: &'__a Self::Inputs<#input_lifetime>) -> &'__a Self::First<#input_lifetime> {
+                    #first_input
+                }
+            }
+        }
+    });
+
+    // Only outputs borrowed from all the non-self inputs can be answered by borrowing from the inputs
+    let borrows_from_inputs_impl = if method.output_structure.borrows_from_inputs {
+        Some(quote! {
+            #(#mirrored_attrs)*
+            impl #generic_params #prefix::output::BorrowsFromInputs for #mock_fn_path #generic_args #where_clause {}
+        })
+    } else {
+        None
+    };
+
     let impl_blocks = quote! {
         #(#mirrored_attrs)*
         impl #generic_params #prefix::MockFn for #mock_fn_path #generic_args #where_clause {
@@ -182,9 +314,19 @@ fn def_mock_fn(
             type Response = #response_associated_type;
             type Output<'u> = #output_associated_type;
             const NAME: &'static str = #mock_fn_name;
+            const INPUT_NAMES: &'static [&'static str] = &[#(#input_names),*];
 
             #debug_inputs_fn
+            #debug_input_fn
+            #custom_debug_input_fn
         }
+
+        #named_inputs_impl
+        #(#debug_expected_impls)*
+
+        #first_input_impl
+        #borrows_from_inputs_impl
+        #callback_arg_impl
     };
 
     let mock_fn_def = if let Some(non_generic_ident) = &method.non_generic_mock_entry_ident {
@@ -197,6 +339,14 @@ fn def_mock_fn(
             _ => None,
         };
 
+        // The opaque type must expose the optional traits implemented for the MockFn
+        let extra_bounds = [
+            first_ty.map(|ty| quote! { + for<#input_lifetime> #prefix::macro_api::FirstInput<First<#input_lifetime> = #ty> }),
+            callback_ty.map(|ty| quote! { + for<#input_lifetime> #prefix::macro_api::CallbackArg<Arg<#input_lifetime> = #ty> }),
+            borrows_from_inputs_impl.as_ref().map(|_| quote! { + #prefix::output::BorrowsFromInputs }),
+        ];
+        let extra_bounds = extra_bounds.iter().flatten();
+
         MockFnDef {
             mock_fn_struct_item: gen_mock_fn_struct_item(non_generic_ident),
             impl_details: quote! {
@@ -206,7 +356,7 @@ fn def_mock_fn(
                     ) -> impl for<#input_lifetime> #prefix::MockFn<
                         Inputs<#input_lifetime> = (#(#inputs_tuple),*),
                         Response = #response_associated_type,
-                    >
+                    > #(#extra_bounds)*
                         #where_clause
                     {
                         #mock_fn_ident(#(#untyped_phantoms),*)
@@ -222,13 +372,32 @@ fn def_mock_fn(
     } else {
         MockFnDef {
             mock_fn_struct_item: gen_mock_fn_struct_item(mock_fn_ident),
-            impl_details: impl_blocks,
+            impl_details: quote! {
+                #named_inputs_struct
+                #impl_blocks
+            },
         }
     };
 
     Some(mock_fn_def)
 }
 
+fn find_async_trait_attr(item_trait: &syn::ItemTrait) -> Option<&syn::Attribute> {
+    item_trait
+        .attrs
+        .iter()
+        .find(|attribute| match attribute.style {
+            syn::AttrStyle::Outer => {
+                if let Some(last_segment) = attribute.path.segments.last() {
+                    last_segment.ident == "async_trait"
+                } else {
+                    false
+                }
+            }
+            syn::AttrStyle::Inner(_) => false,
+        })
+}
+
 fn def_method_impl(
     index: usize,
     method: Option<&method::MockMethod>,
@@ -241,11 +410,12 @@ fn def_method_impl(
     };
 
     let prefix = &attr.prefix;
-    let method_sig = &method.method.sig;
+    let method_sig = &method.impl_sig;
     let mirrored_attrs = method.mirrored_attrs();
     let mock_fn_path = method.mock_fn_path(attr);
 
     let inputs_destructuring = method.inputs_destructuring();
+    let inputs_eval_exprs = method.inputs_eval_exprs();
     let eval_generic_args = util::Generics::args(trait_info, Some(method), InferImplTrait(true));
 
     let has_impl_trait_future = matches!(
@@ -253,10 +423,21 @@ fn def_method_impl(
         output::OutputWrapping::ImplTraitFuture(_)
     );
 
+    // Outputs borrowed from the inputs have their own evaluation, inferring the borrowed type
+    let (eval_fn, evaluation, opt_borrowed_type) = if method.output_structure.borrows_from_inputs {
+        (
+            quote! { eval_borrowing_at },
+            quote! { BorrowingEvaluation },
+            Some(quote! { , _ }),
+        )
+    } else {
+        (quote! { eval_at }, quote! { Evaluation }, None)
+    };
+
     let body = if let Some(UnmockFn {
         path: unmock_path,
         params: unmock_params,
-    }) = attr.get_unmock_fn(index)
+    }) = method.unmock_fn(index, attr)
     {
         let opt_dot_await = if method_sig.asyncness.is_some() || has_impl_trait_future {
             Some(util::DotAwait)
@@ -274,29 +455,64 @@ fn def_method_impl(
         };
 
         quote! {
-            match #prefix::macro_api::eval::<#mock_fn_path #eval_generic_args>(&self, (#inputs_destructuring)) {
-                #prefix::macro_api::Evaluation::Evaluated(output) => output,
-                #prefix::macro_api::Evaluation::Skipped((#inputs_destructuring)) => #unmock_expr
+            match #prefix::macro_api::#eval_fn::<#mock_fn_path #eval_generic_args #opt_borrowed_type>(&self, (#inputs_eval_exprs), __call_site) {
+                #prefix::macro_api::#evaluation::Evaluated(output) => output,
+                #prefix::macro_api::#evaluation::Skipped((#inputs_destructuring)) => #unmock_expr
             }
         }
     } else {
         quote! {
-            #prefix::macro_api::eval::<#mock_fn_path #eval_generic_args>(&self, (#inputs_destructuring)).unwrap(&self)
+            #prefix::macro_api::#eval_fn::<#mock_fn_path #eval_generic_args #opt_borrowed_type>(&self, (#inputs_eval_exprs), __call_site).unwrap(&self)
         }
     };
 
-    let body = if has_impl_trait_future {
-        quote! {
-            async move { #body }
+    // Reference outputs are dereferenced so that autoref specialization picks the right debug trait
+    let output_deref = match method.output_structure.ownership {
+        output::OutputOwnership::Owned | output::OutputOwnership::Mixed => None,
+        output::OutputOwnership::SelfReference
+        | output::OutputOwnership::ParamReference
+        | output::OutputOwnership::StaticReference => Some(quote! { * }),
+    };
+
+    // The output of the call, mocked or unmocked, is registered for call tree tracing.
+    // The trace handle is taken first, because the call may consume `self`:
+    let body = quote! {
+        {
+            let __trace = #prefix::macro_api::trace_start(&self);
+            #prefix::macro_api::trace_output(__trace, #body, |output| {
+                use #prefix::macro_api::{ProperDebug, NoDebug};
+                (#output_deref output).unimock_try_debug_opt()
+            })
         }
-    } else {
-        body
+    };
+
+    // The call site is captured before any future is created, as futures can't track the caller.
+    // Futures are traced in the call tree context of their caller, as they may be polled anywhere.
+    let async_fn = method_sig
+        .asyncness
+        .map(|_| async_fn::AsyncFn::new(find_async_trait_attr(trait_info.item)));
+    let body = match &async_fn {
+        Some(async_fn::AsyncFn::AsyncTrait { .. }) => quote! {
+            ::std::boxed::Box::pin(#prefix::macro_api::trace_future(async move { #body }))
+        },
+        Some(async_fn::AsyncFn::Native) => quote! {
+            #prefix::macro_api::trace_future(async move { #body })
+        },
+        None if has_impl_trait_future => quote! {
+            #prefix::macro_api::trace_future(async move { #body })
+        },
+        None => body,
+    };
+    let method_sig = match &async_fn {
+        Some(async_fn) => async_fn.desugar_sig(&trait_info.item.generics, method_sig),
+        None => method_sig.clone(),
     };
 
     quote! {
         #(#mirrored_attrs)*
         #[track_caller]
         #method_sig {
+            let __call_site = ::core::panic::Location::caller();
             #body
         }
     }
@@ -4,6 +4,8 @@ use syn::{parse_quote, visit_mut::VisitMut};
 pub struct OutputStructure {
     pub wrapping: OutputWrapping,
     pub ownership: OutputOwnership,
+    /// Whether the output is a reference borrowed from all the non-self inputs
+    pub borrows_from_inputs: bool,
     response_ty: AssociatedInnerType,
     output_ty: AssociatedInnerType,
 }
@@ -32,6 +34,9 @@ impl OutputStructure {
             AssociatedInnerType::Typed(inner_type) => {
                 quote! { #inner_type }
             }
+            AssociatedInnerType::Verbatim(ty) => {
+                return quote! { #ty };
+            }
         };
 
         let response_type_ident = self.ownership.response_type_ident();
@@ -41,6 +46,7 @@ impl OutputStructure {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum OutputWrapping {
     None,
     ImplTraitFuture(syn::TraitItemType),
@@ -79,6 +85,7 @@ pub fn determine_output_structure(
         syn::ReturnType::Default => OutputStructure {
             wrapping: OutputWrapping::None,
             ownership: OutputOwnership::Owned,
+            borrows_from_inputs: false,
             response_ty: AssociatedInnerType::Unit,
             output_ty: AssociatedInnerType::Unit,
         },
@@ -91,13 +98,14 @@ pub fn determine_output_structure(
 
                 OutputStructure {
                     wrapping: OutputWrapping::None,
-                    ownership: determine_reference_ownership(sig, type_reference),
+                    borrows_from_inputs: borrows_from_all_inputs(sig, type_reference),
                     response_ty: AssociatedInnerType::new_static(inner_ty, &borrow_info),
                     output_ty: AssociatedInnerType::new_gat(
                         *type_reference.elem.clone(),
                         &borrow_info,
                         &ownership,
                     ),
+                    ownership,
                 }
             }
             syn::Type::Path(path)
@@ -132,51 +140,15 @@ pub fn determine_owned_or_mixed_output_structure(
     };
 
     match (ownership, inner_ty) {
-        (OutputOwnership::Mixed, syn::Type::Tuple(tuple)) => {
-            let mut response_ty_tuple = syn::TypeTuple {
-                paren_token: syn::token::Paren::default(),
-                elems: Default::default(),
-            };
-            let mut output_ty_tuple = syn::TypeTuple {
-                paren_token: syn::token::Paren::default(),
-                elems: Default::default(),
-            };
-
-            for elem in tuple.elems {
-                match elem {
-                    syn::Type::Reference(reference) => {
-                        let elem = reference.elem;
-
-                        if reference.mutability.is_some() {
-                            panic!("TODO: Mutable references in tuples");
-                        }
-
-                        response_ty_tuple.elems.push(parse_quote! {
-                            #prefix::output::Borrowed<#elem>
-                        });
-                        output_ty_tuple.elems.push(parse_quote! {
-                            #prefix::output::Borrowed<#elem>
-                        });
-                    }
-                    owned => {
-                        response_ty_tuple.elems.push(parse_quote! {
-                            #prefix::output::Owned<#owned>
-                        });
-                        output_ty_tuple.elems.push(parse_quote! {
-                            #prefix::output::Owned<#owned>
-                        });
-                    }
-                }
-            }
-
-            response_ty_tuple.elems.push_punct(Default::default());
-            output_ty_tuple.elems.push_punct(Default::default());
+        (OutputOwnership::Mixed, inner_ty) if !is_flat_mixed(&inner_ty) => {
+            let shape = MixedShape::new(prefix, sig, ty);
 
             OutputStructure {
                 wrapping: OutputWrapping::None,
                 ownership: OutputOwnership::Mixed,
-                response_ty: AssociatedInnerType::Typed(syn::Type::Tuple(response_ty_tuple)),
-                output_ty: AssociatedInnerType::Typed(syn::Type::Tuple(output_ty_tuple)),
+                borrows_from_inputs: false,
+                response_ty: AssociatedInnerType::Verbatim(shape.response),
+                output_ty: AssociatedInnerType::Verbatim(shape.output),
             }
         }
         (ownership, inner_ty) => {
@@ -186,6 +158,7 @@ pub fn determine_owned_or_mixed_output_structure(
             OutputStructure {
                 wrapping: OutputWrapping::None,
                 ownership,
+                borrows_from_inputs: false,
                 response_ty,
                 output_ty,
             }
@@ -193,6 +166,226 @@ pub fn determine_owned_or_mixed_output_structure(
     }
 }
 
+/// Whether the type is one of the non-nested mixed types, e.g. `Option<&T>`
+fn is_flat_mixed(ty: &syn::Type) -> bool {
+    let (ident, type_args) = match container_parts(ty) {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    matches!(
+        (ident.to_string().as_str(), type_args.as_slice()),
+        ("Option" | "Vec", [syn::Type::Reference(_)]) | ("Result", [syn::Type::Reference(_), _])
+    )
+}
+
+/// The last ident and the type arguments of a path type
+fn container_parts(ty: &syn::Type) -> Option<(&syn::Ident, Vec<&syn::Type>)> {
+    let type_path = match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => type_path,
+        _ => return None,
+    };
+    let last_segment = type_path.path.segments.last()?;
+    let type_args = match &last_segment.arguments {
+        syn::PathArguments::AngleBracketed(bracketed) => bracketed
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Some((&last_segment.ident, type_args))
+}
+
+/// Response and output shapes of a (part of a) mixed output type.
+///
+/// Containers are composed recursively, e.g. `Option<Vec<&T>>` becomes `Mixed<Option<Mixed<Vec<&'static T>>>>`.
+/// Unknown generic types keep their path, with each type argument converted into a shape,
+/// so users can implement `Respond` and `Output` for their own types.
+struct MixedShape {
+    response: syn::Type,
+    output: syn::Type,
+}
+
+impl MixedShape {
+    fn new(prefix: &syn::Path, sig: &syn::Signature, ty: &syn::Type) -> Self {
+        if !borrows_from_self(sig, ty) {
+            let owned = static_type(ty);
+            return Self::same(parse_quote! { #prefix::output::Owned<#owned> });
+        }
+
+        match ty {
+            syn::Type::Reference(reference) => {
+                let elem = static_type(&reference.elem);
+                Self::same(parse_quote! { #prefix::output::Borrowed<#elem> })
+            }
+            syn::Type::Paren(paren) => Self::new(prefix, sig, &paren.elem),
+            syn::Type::Group(group) => Self::new(prefix, sig, &group.elem),
+            syn::Type::Tuple(tuple) => {
+                let elems: Vec<_> = tuple
+                    .elems
+                    .iter()
+                    .map(|elem| Self::new(prefix, sig, elem))
+                    .collect();
+                let responses = elems.iter().map(|elem| &elem.response);
+                let outputs = elems.iter().map(|elem| &elem.output);
+
+                Self {
+                    response: parse_quote! { #prefix::output::Mixed<(#(#responses,)*)> },
+                    output: parse_quote! { #prefix::output::Mixed<(#(#outputs,)*)> },
+                }
+            }
+            syn::Type::Path(type_path) if type_path.qself.is_none() => {
+                Self::from_path(prefix, sig, ty, type_path)
+            }
+            _ => {
+                let owned = static_type(ty);
+                Self::same(parse_quote! { #prefix::output::Owned<#owned> })
+            }
+        }
+    }
+
+    fn from_path(
+        prefix: &syn::Path,
+        sig: &syn::Signature,
+        ty: &syn::Type,
+        type_path: &syn::TypePath,
+    ) -> Self {
+        let (ident, type_args) = container_parts(ty).unwrap();
+
+        match (ident.to_string().as_str(), type_args.as_slice()) {
+            ("Option" | "Vec", [syn::Type::Reference(reference)]) => {
+                let elem = static_type(&reference.elem);
+                Self::mixed(
+                    prefix,
+                    type_path,
+                    vec![parse_quote! { &'static #elem }],
+                    vec![parse_quote! { &'u #elem }],
+                )
+            }
+            ("Option" | "Vec", [elem]) => {
+                let elem = Self::new(prefix, sig, elem);
+                Self::mixed(prefix, type_path, vec![elem.response], vec![elem.output])
+            }
+            ("Result", [syn::Type::Reference(reference), error]) => {
+                let elem = static_type(&reference.elem);
+                let error = static_type(error);
+                Self::mixed(
+                    prefix,
+                    type_path,
+                    vec![parse_quote! { &'static #elem }, error.clone()],
+                    vec![parse_quote! { &'u #elem }, error],
+                )
+            }
+            ("Result", [ok, error]) => {
+                let ok = Self::new(prefix, sig, ok);
+                let error = static_type(error);
+                Self::mixed(
+                    prefix,
+                    type_path,
+                    vec![ok.response, error.clone()],
+                    vec![ok.output, error],
+                )
+            }
+            ("HashMap" | "BTreeMap", [key, value]) => {
+                let key = static_type(key);
+                let value = Self::new(prefix, sig, value);
+                Self::mixed(
+                    prefix,
+                    type_path,
+                    vec![key.clone(), value.response],
+                    vec![key, value.output],
+                )
+            }
+            (_, type_args) => {
+                let shapes: Vec<_> = type_args
+                    .iter()
+                    .map(|type_arg| Self::new(prefix, sig, type_arg))
+                    .collect();
+
+                Self {
+                    response: with_type_args(
+                        type_path,
+                        shapes.iter().map(|shape| shape.response.clone()).collect(),
+                    ),
+                    output: with_type_args(
+                        type_path,
+                        shapes.into_iter().map(|shape| shape.output).collect(),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn mixed(
+        prefix: &syn::Path,
+        type_path: &syn::TypePath,
+        response_args: Vec<syn::Type>,
+        output_args: Vec<syn::Type>,
+    ) -> Self {
+        let response = with_type_args(type_path, response_args);
+        let output = with_type_args(type_path, output_args);
+
+        Self {
+            response: parse_quote! { #prefix::output::Mixed<#response> },
+            output: parse_quote! { #prefix::output::Mixed<#output> },
+        }
+    }
+
+    fn same(ty: syn::Type) -> Self {
+        Self {
+            response: ty.clone(),
+            output: ty,
+        }
+    }
+}
+
+/// Whether the type contains a reference borrowed from `self`
+fn borrows_from_self(sig: &syn::Signature, ty: &syn::Type) -> bool {
+    let borrow_info = ReturnTypeAnalyzer::analyze_borrows(sig, &mut ty.clone());
+
+    !borrow_info.has_input_lifetime
+        && (borrow_info.has_elided_reference || borrow_info.has_self_reference)
+}
+
+fn static_type(ty: &syn::Type) -> syn::Type {
+    let mut ty = ty.clone();
+    rename_lifetimes(&mut ty, &mut |_| Some("'static"));
+    ty
+}
+
+/// Replace the type arguments of the last path segment, and make other lifetime arguments `'static`
+fn with_type_args(type_path: &syn::TypePath, type_args: Vec<syn::Type>) -> syn::Type {
+    let mut type_path = type_path.clone();
+    let mut type_args = type_args.into_iter();
+
+    if let Some(syn::PathSegment {
+        arguments: syn::PathArguments::AngleBracketed(bracketed),
+        ..
+    }) = type_path.path.segments.last_mut()
+    {
+        for arg in bracketed.args.iter_mut() {
+            match arg {
+                syn::GenericArgument::Type(ty) => {
+                    if let Some(type_arg) = type_args.next() {
+                        *ty = type_arg;
+                    }
+                }
+                syn::GenericArgument::Lifetime(lifetime) => {
+                    *lifetime = syn::Lifetime::new("'static", lifetime.span());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    syn::Type::Path(type_path)
+}
+
 fn is_self_segment(segment: Option<&syn::PathSegment>) -> bool {
     match segment {
         None => false,
@@ -288,6 +481,53 @@ fn determine_reference_ownership(
     }
 }
 
+/// Whether a reference output has a lifetime that is the lifetime of all the non-self inputs
+fn borrows_from_all_inputs(sig: &syn::Signature, type_reference: &syn::TypeReference) -> bool {
+    let lifetime = match &type_reference.lifetime {
+        Some(lifetime) => lifetime,
+        None => return false,
+    };
+    match find_param_lifetime(sig, &lifetime.ident) {
+        Some(index) if index > 0 => {}
+        _ => return false,
+    }
+
+    struct InputLifetimes<'s> {
+        lifetime: &'s syn::Lifetime,
+        all_equal: bool,
+    }
+
+    impl<'s> syn::visit_mut::VisitMut for InputLifetimes<'s> {
+        fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+            if reference.lifetime.is_none() {
+                self.all_equal = false;
+            }
+            syn::visit_mut::visit_type_reference_mut(self, reference);
+        }
+
+        fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+            if lifetime.ident != self.lifetime.ident {
+                self.all_equal = false;
+            }
+        }
+    }
+
+    let mut input_lifetimes = InputLifetimes {
+        lifetime,
+        all_equal: true,
+    };
+    for (index, fn_arg) in sig.inputs.iter().enumerate() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            match (index, pat_type.pat.as_ref()) {
+                (0, syn::Pat::Ident(pat_ident)) if pat_ident.ident == "self" => {}
+                _ => input_lifetimes.visit_type_mut(&mut pat_type.ty.as_ref().clone()),
+            }
+        }
+    }
+
+    input_lifetimes.all_equal
+}
+
 fn find_param_lifetime(sig: &syn::Signature, lifetime_ident: &syn::Ident) -> Option<usize> {
     for (index, fn_arg) in sig.inputs.iter().enumerate() {
         match fn_arg {
@@ -314,10 +554,13 @@ fn find_param_lifetime(sig: &syn::Signature, lifetime_ident: &syn::Ident) -> Opt
 }
 
 #[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 enum AssociatedInnerType {
     Unit,
     Typed(syn::Type),
     SameAsResponse,
+    // A full shape type, not wrapped by the ownership type
+    Verbatim(syn::Type),
 }
 
 impl AssociatedInnerType {
@@ -215,6 +215,15 @@ pub fn substitute_lifetimes(ty: &syn::Type, lifetime: &syn::Lifetime) -> syn::Ty
             *lifetime = self.lifetime.clone();
             syn::visit_mut::visit_lifetime_mut(self, lifetime);
         }
+
+        // Lifetimes in function arguments are higher-ranked, and must stay that way:
+        fn visit_parenthesized_generic_arguments_mut(
+            &mut self,
+            _: &mut syn::ParenthesizedGenericArguments,
+        ) {
+        }
+
+        fn visit_type_bare_fn_mut(&mut self, _: &mut syn::TypeBareFn) {}
     }
 
     let mut replace = LifetimeReplace { lifetime };